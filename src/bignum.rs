@@ -0,0 +1,89 @@
+use std::{
+    fmt::{self, Display},
+    ops::Mul,
+};
+
+/// An unsigned number of any size, stored as 64 bit words starting with the least significant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BigNumber {
+    words: Vec<u64>,
+}
+
+impl BigNumber {
+    pub fn from_words(mut words: Vec<u64>) -> Self {
+        while words.last() == Some(&0) {
+            words.pop();
+        }
+        Self { words }
+    }
+}
+
+impl From<u64> for BigNumber {
+    fn from(n: u64) -> Self {
+        Self::from_words(vec![n])
+    }
+}
+
+impl Mul for &BigNumber {
+    type Output = BigNumber;
+
+    fn mul(self, rhs: &BigNumber) -> BigNumber {
+        let mut words = vec![0; self.words.len() + rhs.words.len()];
+        for (i, &a) in self.words.iter().enumerate() {
+            let mut carry = 0;
+            for (j, &b) in rhs.words.iter().enumerate() {
+                let n = words[i + j] as u128 + a as u128 * b as u128 + carry;
+                words[i + j] = n as u64;
+                carry = n >> 64;
+            }
+            words[i + rhs.words.len()] = carry as u64;
+        }
+        BigNumber::from_words(words)
+    }
+}
+
+impl Display for BigNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const BASE: u128 = 10_000_000_000_000_000_000;
+
+        // split into groups of 19 decimal digits, the least significant first
+        let mut words = self.words.clone();
+        let mut groups = Vec::new();
+        while !words.is_empty() {
+            let mut rem = 0;
+            for word in words.iter_mut().rev() {
+                let n = rem << 64 | *word as u128;
+                *word = (n / BASE) as u64;
+                rem = n % BASE;
+            }
+            groups.push(rem as u64);
+            while words.last() == Some(&0) {
+                words.pop();
+            }
+        }
+
+        let mut groups = groups.iter().rev();
+        write!(f, "{}", groups.next().unwrap_or(&0))?;
+        groups.try_for_each(|group| write!(f, "{:019}", group))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_big_number() {
+        let n = BigNumber::from(u64::MAX);
+        assert_eq!(n.to_string(), "18446744073709551615");
+        assert_eq!(
+            (&n * &n).to_string(),
+            "340282366920938463426481119284349108225"
+        );
+        assert_eq!(BigNumber::from(0).to_string(), "0");
+        assert_eq!(
+            BigNumber::from_words(vec![0, 1]).to_string(),
+            "18446744073709551616"
+        );
+    }
+}
@@ -0,0 +1,115 @@
+use aoc_2021::solution;
+use std::{
+    env,
+    error::Error,
+    fmt::Display,
+    fs,
+    io::{self, Read},
+    process,
+    time::{Duration, Instant},
+};
+
+const USAGE: &str = "usage: runner <day> [1|2|all] [input file|-]";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Part {
+    One,
+    Two,
+    All,
+}
+
+impl Part {
+    fn includes(self, part: Part) -> bool {
+        self == Part::All || self == part
+    }
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let out = f();
+    (out, start.elapsed())
+}
+
+fn report(label: &str, answer: &dyn Display, elapsed: Duration) {
+    let answer = answer.to_string();
+    if answer.contains('\n') {
+        println!("{} ({:?}):\n{}", label, elapsed, answer);
+    } else {
+        println!("{} ({:?}): {}", label, elapsed, answer);
+    }
+}
+
+fn run(day: u8, part: Part, input: &str) -> Result<(), Box<dyn Error>> {
+    let day = solution::find(day).ok_or_else(|| format!("day {} is not implemented", day))?;
+    println!("Day {}", day.day);
+
+    let (parsed, elapsed) = timed(|| day.parse(input));
+    println!("Generator ({:?})", elapsed);
+    let parsed = parsed?;
+
+    if part.includes(Part::One) {
+        let (answer, elapsed) = timed(|| parsed.part1());
+        report("Part 1", &answer, elapsed);
+    }
+    if part.includes(Part::Two) {
+        let (answer, elapsed) = timed(|| parsed.part2());
+        report("Part 2", &answer, elapsed);
+    }
+
+    Ok(())
+}
+
+fn read_input(path: Option<&str>) -> io::Result<String> {
+    let mut input = match path {
+        None | Some("-") => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+            input
+        }
+        Some(path) => fs::read_to_string(path)?,
+    };
+
+    // cargo-aoc hands the solvers the input without the trailing newline, do the same
+    let len = input.trim_end_matches(['\r', '\n']).len();
+    input.truncate(len);
+
+    Ok(input)
+}
+
+fn parse_args(args: &[String]) -> Result<(u8, Part, Option<&str>), Box<dyn Error>> {
+    let day = args
+        .first()
+        .ok_or("missing day")?
+        .trim_start_matches("day")
+        .parse()?;
+
+    let part = match args.get(1).map(String::as_str) {
+        None | Some("all") => Part::All,
+        Some("1") => Part::One,
+        Some("2") => Part::Two,
+        Some(part) => return Err(format!("unknown part `{}`", part).into()),
+    };
+
+    Ok((day, part, args.get(2).map(String::as_str)))
+}
+
+fn main() {
+    let args: Vec<_> = env::args().skip(1).collect();
+
+    let (day, part, path) = match parse_args(&args) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("error: {}\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+
+    let result = read_input(path)
+        .map_err(Into::into)
+        .and_then(|input| run(day, part, &input));
+
+    if let Err(e) = result {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,352 @@
+use crate::{
+    grid::Grid,
+    parse::{blocks, comma_list, sections, IResult},
+};
+use nom::{
+    branch::alt,
+    character::complete::{line_ending, space0, space1, u32},
+    combinator::{cut, eof, peek},
+    error::{context, VerboseError, VerboseErrorKind},
+    multi::{count, separated_list1},
+    sequence::{pair, preceded, terminated},
+    Parser,
+};
+
+/// A set of cells that wins the game once all of them are marked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Line {
+    Row(usize),
+    Column(usize),
+    /// From the top left to the bottom right.
+    Diagonal,
+    /// From the top right to the bottom left.
+    AntiDiagonal,
+    FullCard,
+}
+
+impl Line {
+    pub fn positions(self, size: usize) -> Vec<(usize, usize)> {
+        match self {
+            Line::Row(r) => (0..size).map(|c| (r, c)).collect(),
+            Line::Column(c) => (0..size).map(|r| (r, c)).collect(),
+            Line::Diagonal => (0..size).map(|i| (i, i)).collect(),
+            Line::AntiDiagonal => (0..size).map(|i| (i, size - 1 - i)).collect(),
+            Line::FullCard => (0..size)
+                .flat_map(|r| (0..size).map(move |c| (r, c)))
+                .collect(),
+        }
+    }
+}
+
+/// Which kinds of lines win the game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WinConditions {
+    pub rows: bool,
+    pub columns: bool,
+    pub diagonals: bool,
+    pub full_card: bool,
+}
+
+impl WinConditions {
+    /// Any complete row or column wins, diagonals don't count.
+    pub const STANDARD: WinConditions = WinConditions {
+        rows: true,
+        columns: true,
+        diagonals: false,
+        full_card: false,
+    };
+
+    /// Only marking every number on the board wins.
+    pub const FULL_CARD: WinConditions = WinConditions {
+        rows: false,
+        columns: false,
+        diagonals: false,
+        full_card: true,
+    };
+
+    /// The winning lines of a board of `size`, rows first, then columns, diagonals and the full
+    /// card.
+    pub fn lines(self, size: usize) -> impl Iterator<Item = Line> {
+        let rows = (0..size).filter(move |_| self.rows).map(Line::Row);
+        let columns = (0..size).filter(move |_| self.columns).map(Line::Column);
+        let diagonals = [Line::Diagonal, Line::AntiDiagonal]
+            .into_iter()
+            .filter(move |_| self.diagonals);
+        let full_card = Some(Line::FullCard).filter(|_| self.full_card);
+        rows.chain(columns).chain(diagonals).chain(full_card)
+    }
+}
+
+/// A square bingo board and which of its numbers were drawn so far.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Board {
+    numbers: Grid<u32>,
+    marked: Grid<bool>,
+}
+
+impl Board {
+    /// # Panics
+    ///
+    /// If the grid is not square.
+    pub fn new(numbers: Grid<u32>) -> Self {
+        assert_eq!(numbers.rows(), numbers.cols(), "bingo boards are square");
+        let marked = numbers.map(|_| false);
+        Self { numbers, marked }
+    }
+
+    pub fn size(&self) -> usize {
+        self.numbers.rows()
+    }
+
+    pub fn numbers(&self) -> &Grid<u32> {
+        &self.numbers
+    }
+
+    pub fn is_marked(&self, pos: (usize, usize)) -> bool {
+        self.marked[pos]
+    }
+
+    /// Marks every cell holding `number`, returns whether there was one.
+    pub fn mark(&mut self, number: u32) -> bool {
+        let mut found = false;
+        for (pos, &n) in self.numbers.indexed_iter() {
+            if n == number {
+                self.marked[pos] = true;
+                found = true;
+            }
+        }
+        found
+    }
+
+    /// The first of the lines allowed by `conditions` that is completely marked.
+    pub fn winning_line(&self, conditions: WinConditions) -> Option<Line> {
+        conditions.lines(self.size()).find(|line| {
+            line.positions(self.size())
+                .into_iter()
+                .all(|pos| self.is_marked(pos))
+        })
+    }
+
+    pub fn unmarked_sum(&self) -> u32 {
+        self.numbers
+            .iter()
+            .zip(self.marked.iter())
+            .filter(|(_, &marked)| !marked)
+            .map(|(n, _)| n)
+            .sum()
+    }
+}
+
+/// The numbers in the order they are drawn and the boards they are drawn for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bingo {
+    pub numbers: Vec<u32>,
+    pub boards: Vec<Board>,
+}
+
+impl Bingo {
+    /// Plays the game once for all boards, a board stops playing once it won.
+    pub fn timeline(&self, conditions: WinConditions) -> Timeline {
+        let mut boards = self.boards.clone();
+        let mut wins = vec![None; boards.len()];
+        let mut left = boards.len();
+
+        for (draw, &number) in self.numbers.iter().enumerate() {
+            if left == 0 {
+                break;
+            }
+            for (board, win) in boards.iter_mut().zip(&mut wins) {
+                if win.is_some() || !board.mark(number) {
+                    continue;
+                }
+                if let Some(line) = board.winning_line(conditions) {
+                    left -= 1;
+                    *win = Some(Win {
+                        draw,
+                        number,
+                        line,
+                        score: board.unmarked_sum() * number,
+                    });
+                }
+            }
+        }
+
+        Timeline { wins }
+    }
+}
+
+/// How a board won.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Win {
+    /// Index of the number that completed `line`.
+    pub draw: usize,
+    pub number: u32,
+    pub line: Line,
+    /// The sum of the unmarked numbers times the winning number.
+    pub score: u32,
+}
+
+/// The outcome of a game for every board.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timeline {
+    /// The win of each board in input order, `None` for boards that never won.
+    pub wins: Vec<Option<Win>>,
+}
+
+impl Timeline {
+    pub fn board(&self, i: usize) -> Option<&Win> {
+        self.wins.get(i)?.as_ref()
+    }
+
+    /// The indices and wins of the boards that won, in the order they won. Boards that won on
+    /// the same number are in input order.
+    pub fn ranking(&self) -> Vec<(usize, Win)> {
+        let mut ranking: Vec<_> = self
+            .wins
+            .iter()
+            .enumerate()
+            .filter_map(|(i, win)| Some((i, (*win)?)))
+            .collect();
+        ranking.sort_by_key(|&(i, win)| (win.draw, i));
+        ranking
+    }
+
+    /// The board that finished in place `n`, starting at 0 for the first winner.
+    pub fn nth(&self, n: usize) -> Option<(usize, Win)> {
+        self.ranking().get(n).copied()
+    }
+
+    pub fn first(&self) -> Option<(usize, Win)> {
+        self.nth(0)
+    }
+
+    pub fn last(&self) -> Option<(usize, Win)> {
+        self.ranking().last().copied()
+    }
+
+    /// The boards that were still playing when the numbers ran out.
+    pub fn never_won(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.wins.len()).filter(|&i| self.wins[i].is_none())
+    }
+}
+
+fn failure<'a>(at: &'a str, expected: &'static str) -> nom::Err<VerboseError<&'a str>> {
+    nom::Err::Failure(VerboseError {
+        errors: vec![(at, VerboseErrorKind::Context(expected))],
+    })
+}
+
+/// Parses a square board of whitespace separated numbers, its size is taken from the first row.
+pub fn board(input: &str) -> IResult<'_, Board> {
+    let number = || context("a number", u32);
+    let line_end = || preceded(space0, peek(alt((line_ending, eof))));
+
+    let (rest, first) = terminated(
+        preceded(space0, separated_list1(space1, number())),
+        context("end of line", cut(line_end())),
+    )(input)?;
+    let size = first.len();
+
+    let row = terminated(
+        count(preceded(space0, number()), size),
+        context("end of line", line_end()),
+    );
+    let (rest, rows) =
+        count(preceded(line_ending, cut(row)), size - 1)(rest).map_err(|e| match e {
+            nom::Err::Error(e) => failure(e.errors[0].0, "another row of the board"),
+            e => e,
+        })?;
+
+    // a board with too many rows would otherwise only fail after the last board
+    if let Ok((next, _)) = pair(line_ending::<_, ()>, space0)(rest) {
+        if !next.is_empty() && line_ending::<_, ()>(next).is_err() {
+            return Err(failure(rest, "an empty line"));
+        }
+    }
+
+    let cells = first
+        .into_iter()
+        .chain(rows.into_iter().flatten())
+        .collect();
+    Ok((rest, Board::new(Grid::new(size, size, cells))))
+}
+
+/// Parses the drawn numbers on the first line, followed by the boards separated by empty lines.
+pub fn bingo(input: &str) -> IResult<'_, Bingo> {
+    sections(comma_list(context("a number", u32)), blocks(board))
+        .map(|(numbers, boards)| Bingo { numbers, boards })
+        .parse(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parse_all;
+
+    fn board_of(input: &str) -> Result<Board, crate::error::ParseError> {
+        parse_all(4, input, board)
+    }
+
+    #[test]
+    fn test_parse() {
+        let board = board_of(" 1  2  3\n 4 15  6\n 7  8 99 ").unwrap();
+        assert_eq!(board.size(), 3);
+        assert_eq!(board.numbers()[(1, 1)], 15);
+
+        let err = board_of("1 2 3\n4 5\n7 8 9").unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.expected.as_str()),
+            (2, 4, "a number")
+        );
+        let err = board_of("1 2 3\n4 5 6 7\n7 8 9").unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.expected.as_str()),
+            (2, 7, "end of line")
+        );
+        let err = board_of("1 2 3\n4 5 6").unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.expected.as_str()),
+            (2, 6, "another row of the board")
+        );
+        let err = board_of("1 2\n3 4\n5 6").unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.expected.as_str()),
+            (2, 4, "an empty line")
+        );
+
+        let game = parse_all(4, "3,1\n\n1\n\n1 2\n3 4", bingo).unwrap();
+        let sizes: Vec<_> = game.boards.iter().map(Board::size).collect();
+        assert_eq!(sizes, [1, 2]);
+    }
+
+    #[test]
+    fn test_win_conditions() {
+        let mut board = board_of("1 2 3\n4 5 6\n7 8 9").unwrap();
+        let diagonals = WinConditions {
+            diagonals: true,
+            ..WinConditions::STANDARD
+        };
+
+        for n in [3, 5, 7] {
+            board.mark(n);
+        }
+        assert_eq!(board.winning_line(WinConditions::STANDARD), None);
+        assert_eq!(board.winning_line(diagonals), Some(Line::AntiDiagonal));
+
+        for n in [1, 2] {
+            board.mark(n);
+        }
+        assert_eq!(board.winning_line(diagonals), Some(Line::Row(0)));
+        assert_eq!(board.winning_line(WinConditions::FULL_CARD), None);
+        assert_eq!(board.unmarked_sum(), 4 + 6 + 8 + 9);
+
+        for n in [4, 6, 8, 9] {
+            board.mark(n);
+        }
+        assert_eq!(
+            board.winning_line(WinConditions::FULL_CARD),
+            Some(Line::FullCard)
+        );
+        assert_eq!(board.unmarked_sum(), 0);
+    }
+}
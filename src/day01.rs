@@ -1,30 +1,190 @@
 use super::*;
-use itertools::Itertools;
+use std::{
+    fmt,
+    io::{self, BufRead},
+};
 
 #[aoc_generator(day1)]
-pub fn input_generator(input: &str) -> Result<Vec<u32>, nom::Err<()>> {
-    use nom::{
-        character::complete::{line_ending, u32},
-        multi::separated_list1,
-    };
+pub fn input_generator(input: &str) -> Result<Vec<u32>, ParseError> {
+    use crate::parse::{lines, parse_all};
+    use nom::{character::complete::u32, error::context};
 
-    let mut parse = separated_list1(line_ending, u32);
-    parse(input).map(|(_, p)| p)
+    parse_all(1, input, lines(context("a depth", u32)))
+}
+
+/// Positions of the depths that make the sum of a sliding `window` larger than the one before.
+///
+/// Two consecutive windows only differ in their first and last depth, so these are compared
+/// directly instead of summing the windows up.
+pub fn increases(depths: &[u32], window: usize) -> impl Iterator<Item = usize> + '_ {
+    depths
+        .iter()
+        .zip(depths.iter().skip(window))
+        .enumerate()
+        .filter(|(_, (a, b))| a < b)
+        .map(move |(i, _)| i + window)
+}
+
+pub fn count_increases(depths: &[u32], window: usize) -> usize {
+    increases(depths, window).count()
+}
+
+/// Counts the increases of a sliding window over depths that arrive one at a time, only the
+/// last `window` depths are kept around.
+#[derive(Debug, Clone)]
+pub struct IncreaseCounter {
+    window: usize,
+    recent: Vec<u32>,
+    oldest: usize,
+    count: usize,
+}
+
+impl IncreaseCounter {
+    pub fn new(window: usize) -> Self {
+        Self {
+            window,
+            recent: Vec::with_capacity(window),
+            oldest: 0,
+            count: 0,
+        }
+    }
+
+    /// Adds the next depth, returns whether it made the window larger than the one before.
+    pub fn push(&mut self, depth: u32) -> bool {
+        if self.window == 0 {
+            return false;
+        }
+        if self.recent.len() < self.window {
+            self.recent.push(depth);
+            return false;
+        }
+
+        let increased = self.recent[self.oldest] < depth;
+        self.recent[self.oldest] = depth;
+        self.oldest = (self.oldest + 1) % self.window;
+        self.count += increased as usize;
+        increased
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+/// Same as [`count_increases`], but for depths that are not in memory all at once.
+pub fn count_increases_in(depths: impl IntoIterator<Item = u32>, window: usize) -> usize {
+    let mut counter = IncreaseCounter::new(window);
+    for depth in depths {
+        counter.push(depth);
+    }
+    counter.count()
+}
+
+#[derive(Debug)]
+pub enum StreamError {
+    Io(io::Error),
+    Parse(ParseError),
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::Io(e) => e.fmt(f),
+            StreamError::Parse(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for StreamError {}
+
+/// Reads one depth per line from `reader` without loading the whole input.
+///
+/// Like [`input_generator`], empty lines are only accepted at the end of the input. Errors carry
+/// the number of the offending line.
+pub struct DepthReader<R> {
+    reader: R,
+    line: String,
+    line_number: usize,
+    // an empty line that is only an error if more depths follow
+    blank: Option<ParseError>,
+}
+
+impl<R: BufRead> DepthReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            line: String::new(),
+            line_number: 0,
+            blank: None,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for DepthReader<R> {
+    type Item = Result<u32, StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        use crate::parse::parse_all;
+        use nom::{character::complete::u32, error::context};
+
+        loop {
+            self.line.clear();
+            match self.reader.read_line(&mut self.line) {
+                Ok(0) => return None,
+                Ok(_) => self.line_number += 1,
+                Err(e) => return Some(Err(StreamError::Io(e))),
+            }
+
+            let at_line = |err| ParseError {
+                line: self.line_number,
+                ..err
+            };
+
+            if self.line.trim().is_empty() {
+                let err = ParseError::new(1, &self.line, &self.line, "a depth");
+                self.blank.get_or_insert(at_line(err));
+                continue;
+            }
+            if let Some(err) = self.blank.take() {
+                return Some(Err(StreamError::Parse(err)));
+            }
+
+            let depth = parse_all(1, &self.line, context("a depth", u32));
+            return Some(depth.map_err(|err| StreamError::Parse(at_line(err))));
+        }
+    }
 }
 
 #[aoc(day1, part1)]
 pub fn solve_part1(depths: &[u32]) -> usize {
-    depths.windows(2).filter(|d| d[0] < d[1]).count()
+    count_increases(depths, 1)
 }
 
 #[aoc(day1, part2)]
 pub fn solve_part2(depths: &[u32]) -> usize {
-    depths
-        .windows(3)
-        .map(|d| d.iter().sum())
-        .tuple_windows()
-        .filter(|(a, b): &(u32, u32)| a < b)
-        .count()
+    count_increases(depths, 3)
+}
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    const DAY: u8 = 1;
+
+    type Input = Vec<u32>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        solve_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        solve_part2(input)
+    }
 }
 
 #[cfg(test)]
@@ -33,6 +193,52 @@ mod tests {
 
     static INPUT: &str = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263";
 
+    #[test]
+    fn test_increases() {
+        let depths = input_generator(INPUT).unwrap();
+        assert_eq!(
+            increases(&depths, 1).collect::<Vec<_>>(),
+            [1, 2, 3, 5, 6, 7, 9]
+        );
+        assert_eq!(increases(&depths, 3).collect::<Vec<_>>(), [3, 6, 7, 8, 9]);
+        assert_eq!(count_increases(&depths, 10), 0);
+
+        // the sums of these windows would not fit into a u32
+        let deep = [u32::MAX - 1, u32::MAX, u32::MAX, u32::MAX];
+        assert_eq!(count_increases(&deep, 3), 1);
+    }
+
+    #[test]
+    fn test_stream() {
+        let depths = input_generator(INPUT).unwrap();
+        assert_eq!(count_increases_in(depths.iter().copied(), 1), 7);
+        assert_eq!(count_increases_in(depths.iter().copied(), 3), 5);
+
+        let input = format!("{}\r\n\n\n", INPUT.replace('\n', "\r\n"));
+        let (mut part1, mut part2) = (IncreaseCounter::new(1), IncreaseCounter::new(3));
+        for depth in DepthReader::new(input.as_bytes()) {
+            let depth = depth.unwrap();
+            part1.push(depth);
+            part2.push(depth);
+        }
+        assert_eq!((part1.count(), part2.count()), (7, 5));
+    }
+
+    #[test]
+    fn test_stream_errors() {
+        let errors = |input: &str| -> Vec<_> {
+            DepthReader::new(input.as_bytes())
+                .filter_map(|depth| match depth {
+                    Err(StreamError::Parse(e)) => Some((e.line, e.column)),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        assert_eq!(errors("199\n20x\n208\n-3"), [(2, 3), (4, 1)]);
+        assert_eq!(errors("199\n\n \n208\n"), [(2, 1)]);
+    }
+
     #[test]
     fn test_part1() {
         assert_eq!(solve_part1(input_generator(INPUT).unwrap().as_slice()), 7)
@@ -1,57 +1,358 @@
 use super::*;
+use crate::{parse::IResult, solution::Maybe};
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+};
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Command {
     Horizontal(i32),
     Vertical(i32),
 }
 
-#[aoc_generator(day2)]
-pub fn input_generator(input: &str) -> Result<Vec<Command>, nom::Err<()>> {
+const EXPECTED_COMMAND: &str = "`forward`, `down`, `up`, `repeat`, `macro` or a defined macro";
+
+// how many commands a script may expand to
+const MAX_COMMANDS: usize = 1_000_000;
+
+// a script before its macros and repeats are expanded, a repeat keeps its keyword for errors
+#[derive(Debug)]
+enum Statement<'a> {
+    Command(Command),
+    Repeat(&'a str, u32, Vec<Statement<'a>>),
+    Macro(&'a str, Vec<Statement<'a>>),
+    Call(&'a str),
+}
+
+// whitespace and `#` comments
+fn skip(input: &str) -> IResult<'_, ()> {
+    use nom::{
+        branch::alt,
+        character::complete::{char, multispace1, not_line_ending},
+        combinator::value,
+        multi::many0_count,
+        sequence::preceded,
+    };
+
+    value(
+        (),
+        many0_count(alt((multispace1, preceded(char('#'), not_line_ending)))),
+    )(input)
+}
+
+fn name(input: &str) -> IResult<'_, &str> {
     use nom::{
         branch::alt,
         bytes::complete::tag,
-        character::complete::{char, i32, line_ending},
-        combinator::map,
-        multi::separated_list1,
-        sequence::separated_pair,
+        character::complete::{alpha1, alphanumeric1},
+        combinator::recognize,
+        multi::many0_count,
+        sequence::pair,
     };
 
-    let forward = tag("forward");
-    let down = tag("down");
-    let up = tag("up");
+    recognize(pair(
+        alt((alpha1, tag("_"))),
+        many0_count(alt((alphanumeric1, tag("_")))),
+    ))(input)
+}
 
-    let cmd = map(
-        separated_pair(alt((forward, down, up)), char(' '), i32),
-        |(kind, val)| match kind {
-            "forward" => Command::Horizontal(val),
-            "down" => Command::Vertical(val),
-            "up" => Command::Vertical(-val),
-            _ => unreachable!(),
-        },
-    );
-    let mut parse = separated_list1(line_ending, cmd);
+fn block(input: &str) -> IResult<'_, Vec<Statement<'_>>> {
+    use nom::{
+        character::complete::char,
+        error::context,
+        sequence::{delimited, preceded},
+    };
 
-    parse(input).map(|(_, p)| p)
+    delimited(
+        preceded(skip, context("`{`", char('{'))),
+        statements,
+        preceded(skip, context("`}`", char('}'))),
+    )(input)
 }
 
-#[aoc(day2, part1)]
-pub fn solve_part1(cmds: &[Command]) -> i32 {
-    let position = cmds.iter().fold((0, 0), |(x, y), cmd| match cmd {
-        Command::Horizontal(d) => (x + d, y),
-        Command::Vertical(d) => (x, y + d),
-    });
+fn statements(input: &str) -> IResult<'_, Vec<Statement<'_>>> {
+    use nom::{multi::many0, sequence::preceded};
+
+    many0(preceded(skip, statement))(input)
+}
+
+fn statement(input: &str) -> IResult<'_, Statement<'_>> {
+    use nom::{
+        character::complete::{i32, space1, u32},
+        combinator::{cut, map_opt, peek, verify},
+        error::{context, VerboseError, VerboseErrorKind},
+        sequence::{pair, preceded},
+        Parser,
+    };
+
+    let (rest, word) = name(input)?;
+    let command = |cmd: fn(i32) -> Option<Command>| {
+        cut(preceded(space1, context("a distance", map_opt(i32, cmd)))).map(Statement::Command)
+    };
+
+    // once the keyword is known, whatever follows has to match it
+    match word {
+        "forward" => command(|d| Some(Command::Horizontal(d))).parse(rest),
+        "down" => command(|d| Some(Command::Vertical(d))).parse(rest),
+        // the smallest i32 has no positive counterpart
+        "up" => command(|d| d.checked_neg().map(Command::Vertical)).parse(rest),
+        "repeat" => cut(pair(
+            preceded(space1, context("a repeat count", u32)),
+            block,
+        ))
+        .map(|(n, body)| Statement::Repeat(word, n, body))
+        .parse(rest),
+        "macro" => {
+            let macro_name = verify(name, |name: &str| !is_keyword(name));
+            cut(pair(
+                preceded(space1, context("a macro name", macro_name)),
+                block,
+            ))
+            .map(|(name, body)| Statement::Macro(name, body))
+            .parse(rest)
+        }
+        // a macro takes no arguments, so this has to be a misspelled command
+        _ if peek(preceded(space1, i32::<_, VerboseError<&str>>))(rest).is_ok() => {
+            Err(nom::Err::Failure(VerboseError {
+                errors: vec![(input, VerboseErrorKind::Context(EXPECTED_COMMAND))],
+            }))
+        }
+        _ => Ok((rest, Statement::Call(word))),
+    }
+}
+
+fn is_keyword(word: &str) -> bool {
+    matches!(word, "forward" | "down" | "up" | "repeat" | "macro")
+}
+
+// appends `times` copies of `cmds`, as long as the script stays within `MAX_COMMANDS`
+fn extend(
+    input: &str,
+    at: &str,
+    out: &mut Vec<Command>,
+    cmds: &[Command],
+    times: usize,
+) -> Result<(), ParseError> {
+    let len = cmds
+        .len()
+        .checked_mul(times)
+        .and_then(|len| len.checked_add(out.len()));
+    if len.filter(|&len| len <= MAX_COMMANDS).is_none() {
+        let expected = format!("a script of at most {} commands", MAX_COMMANDS);
+        return Err(ParseError::new(2, input, at, expected));
+    }
+
+    for _ in 0..times {
+        out.extend_from_slice(cmds);
+    }
+    Ok(())
+}
+
+fn expand<'a>(
+    input: &str,
+    statements: &[Statement<'a>],
+    macros: &mut HashMap<&'a str, Vec<Command>>,
+    out: &mut Vec<Command>,
+) -> Result<(), ParseError> {
+    for statement in statements {
+        match statement {
+            Statement::Command(cmd) => out.push(*cmd),
+            Statement::Repeat(at, n, body) => {
+                let mut cmds = Vec::new();
+                expand(input, body, macros, &mut cmds)?;
+                extend(input, at, out, &cmds, *n as usize)?;
+            }
+            Statement::Macro(name, body) => {
+                let mut cmds = Vec::new();
+                expand(input, body, macros, &mut cmds)?;
+                macros.insert(name, cmds);
+            }
+            Statement::Call(name) => match macros.get(name) {
+                Some(cmds) => extend(input, name, out, cmds, 1)?,
+                None => return Err(ParseError::new(2, input, name, EXPECTED_COMMAND)),
+            },
+        }
+    }
+    Ok(())
+}
+
+/// Parses a script of `forward`, `down` and `up` commands.
+///
+/// Commands can be repeated with `repeat N { ... }` and named with `macro name { ... }`, a macro
+/// has to be defined before it is used. Everything after a `#` is a comment.
+#[aoc_generator(day2)]
+pub fn input_generator(input: &str) -> Result<Vec<Command>, ParseError> {
+    use crate::parse::parse_all;
+    use nom::{
+        combinator::eof,
+        error::context,
+        sequence::{preceded, terminated},
+    };
 
-    position.0 * position.1
+    let script = terminated(statements, preceded(skip, context(EXPECTED_COMMAND, eof)));
+    let statements = parse_all(2, input, script)?;
+
+    let mut cmds = Vec::new();
+    expand(input, &statements, &mut HashMap::new(), &mut cmds)?;
+    Ok(cmds)
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct State {
+    pub position: i64,
+    pub depth: i64,
+    pub aim: i64,
+}
+
+/// How commands move a submarine.
+pub trait NavigationModel {
+    /// The state after `cmd`, `None` if it does not fit into a `State`.
+    fn apply(&self, state: State, cmd: &Command) -> Option<State>;
+}
+
+/// `down` and `up` change the depth right away.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Direct;
+
+impl NavigationModel for Direct {
+    fn apply(&self, mut state: State, cmd: &Command) -> Option<State> {
+        match *cmd {
+            Command::Horizontal(d) => state.position = state.position.checked_add(d.into())?,
+            Command::Vertical(d) => state.depth = state.depth.checked_add(d.into())?,
+        }
+        Some(state)
+    }
+}
+
+/// `down` and `up` change the aim, moving forward changes the depth by the aim.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Aim;
+
+impl NavigationModel for Aim {
+    fn apply(&self, mut state: State, cmd: &Command) -> Option<State> {
+        match *cmd {
+            Command::Horizontal(d) => {
+                state.position = state.position.checked_add(d.into())?;
+                state.depth = state.depth.checked_add(state.aim.checked_mul(d.into())?)?;
+            }
+            Command::Vertical(d) => state.aim = state.aim.checked_add(d.into())?,
+        }
+        Some(state)
+    }
+}
+
+/// A submarine that remembers every state it went through, starting at the surface.
+#[derive(Debug, Clone)]
+pub struct Submarine<M = Aim> {
+    model: M,
+    trajectory: Vec<State>,
+}
+
+impl<M: NavigationModel + Default> Default for Submarine<M> {
+    fn default() -> Self {
+        Self::new(M::default())
+    }
+}
+
+impl<M: NavigationModel> Submarine<M> {
+    pub fn new(model: M) -> Self {
+        Self {
+            model,
+            trajectory: vec![State::default()],
+        }
+    }
+
+    /// A submarine that followed all `cmds` according to `model`, `None` if it left the range
+    /// of a `State` on the way.
+    pub fn run<'a>(model: M, cmds: impl IntoIterator<Item = &'a Command>) -> Option<Self> {
+        let mut submarine = Self::new(model);
+        for cmd in cmds {
+            submarine.apply(cmd)?;
+        }
+        Some(submarine)
+    }
+
+    /// Follows `cmd` and returns the new state, the submarine stays where it is if the new state
+    /// is out of range.
+    pub fn apply(&mut self, cmd: &Command) -> Option<State> {
+        let state = self.model.apply(self.state(), cmd)?;
+        self.trajectory.push(state);
+        Some(state)
+    }
+
+    pub fn state(&self) -> State {
+        *self.trajectory.last().unwrap()
+    }
+
+    pub fn position(&self) -> i64 {
+        self.state().position
+    }
+
+    pub fn depth(&self) -> i64 {
+        self.state().depth
+    }
+
+    pub fn aim(&self) -> i64 {
+        self.state().aim
+    }
+
+    /// All states so far, the state after step `n` is at index `n`.
+    pub fn trajectory(&self) -> &[State] {
+        &self.trajectory
+    }
+
+    pub fn max_depth(&self) -> i64 {
+        self.trajectory.iter().map(|s| s.depth).max().unwrap()
+    }
+
+    /// The first step after which the submarine was at least `depth` deep.
+    pub fn first_step_below(&self, depth: i64) -> Option<usize> {
+        self.trajectory.iter().position(|s| s.depth >= depth)
+    }
+
+    /// Writes the trajectory as CSV with a header line.
+    pub fn write_csv(&self, mut out: impl Write) -> io::Result<()> {
+        writeln!(out, "step,position,depth,aim")?;
+        for (step, s) in self.trajectory.iter().enumerate() {
+            writeln!(out, "{},{},{},{}", step, s.position, s.depth, s.aim)?;
+        }
+        Ok(())
+    }
+}
+
+#[aoc(day2, part1)]
+pub fn solve_part1(cmds: &[Command]) -> Option<i64> {
+    let submarine = Submarine::run(Direct, cmds)?;
+    submarine.position().checked_mul(submarine.depth())
 }
 
 #[aoc(day2, part2)]
-pub fn solve_part2(cmds: &[Command]) -> i32 {
-    let position = cmds.iter().fold((0, 0, 0), |(x, y, aim), cmd| match cmd {
-        Command::Horizontal(d) => (x + d, y + aim * d, aim),
-        Command::Vertical(d) => (x, y, aim + d),
-    });
+pub fn solve_part2(cmds: &[Command]) -> Option<i64> {
+    let submarine = Submarine::run(Aim, cmds)?;
+    submarine.position().checked_mul(submarine.depth())
+}
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    const DAY: u8 = 2;
 
-    position.0 * position.1
+    type Input = Vec<Command>;
+    type Output1 = Maybe<i64>;
+    type Output2 = Maybe<i64>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        Maybe(solve_part1(input))
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        Maybe(solve_part2(input))
+    }
 }
 
 #[cfg(test)]
@@ -60,13 +361,149 @@ mod tests {
 
     static INPUT: &str = "forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2";
 
+    #[test]
+    fn test_script() {
+        let script = "# dive twice
+macro dive {
+    down 5
+    forward 8
+}
+forward 5
+repeat 2 { dive }  # and come back up a bit
+up 3";
+        let expanded = "forward 5\ndown 5\nforward 8\ndown 5\nforward 8\nup 3";
+        assert_eq!(input_generator(script), input_generator(expanded));
+
+        let nested = "macro step { forward 1 }\nrepeat 2 { repeat 3 { step } down 1 }";
+        assert_eq!(input_generator(nested).unwrap().len(), 8);
+    }
+
+    #[test]
+    fn test_script_errors() {
+        let err = input_generator("forward 5\n  sideways 5").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+        assert_eq!(err.expected, EXPECTED_COMMAND);
+
+        let err = input_generator("forward 5\nzigzag\nmacro zigzag { up 1 }").unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.found.as_str()),
+            (2, 1, "`zigzag`")
+        );
+
+        let err = input_generator("forward x").unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.expected.as_str()),
+            (1, 9, "a distance")
+        );
+
+        let err = input_generator("repeat 2 {\n  forward 1\n").unwrap_err();
+        assert_eq!(err.expected, "`}`");
+
+        let err = input_generator("up -2147483648").unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.expected.as_str()),
+            (1, 4, "a distance")
+        );
+        assert_eq!(
+            input_generator("up -2147483647"),
+            Ok(vec![Command::Vertical(i32::MAX)])
+        );
+
+        let err = input_generator("forward 1\nrepeat 100000 { repeat 100000 { forward 1 } }")
+            .unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+        let err = input_generator("macro m { repeat 1000 { forward 1 } }\nrepeat 1000 { m m }")
+            .unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+        assert_eq!(
+            input_generator("repeat 1000 { repeat 1000 { up 1 } }")
+                .unwrap()
+                .len(),
+            MAX_COMMANDS
+        );
+
+        assert!(input_generator("macro up { down 1 }").is_err());
+        assert!(input_generator("forward 5 }").is_err());
+    }
+
+    #[test]
+    fn test_trajectory() {
+        let submarine = Submarine::run(Aim, &input_generator(INPUT).unwrap()).unwrap();
+        let depths: Vec<_> = submarine.trajectory().iter().map(|s| s.depth).collect();
+        assert_eq!(depths, [0, 0, 0, 40, 40, 40, 60]);
+        assert_eq!(submarine.max_depth(), 60);
+        assert_eq!(submarine.first_step_below(41), Some(6));
+        assert_eq!(submarine.first_step_below(61), None);
+
+        let mut csv = Vec::new();
+        Submarine::run(Aim, &input_generator("forward 5\ndown 5").unwrap())
+            .unwrap()
+            .write_csv(&mut csv)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "step,position,depth,aim\n0,0,0,0\n1,5,0,0\n2,5,0,5\n"
+        );
+    }
+
+    // the aim model, but the submarine never goes deeper than `max_depth`
+    struct Clamped {
+        max_depth: i64,
+    }
+
+    impl NavigationModel for Clamped {
+        fn apply(&self, state: State, cmd: &Command) -> Option<State> {
+            let mut state = Aim.apply(state, cmd)?;
+            state.depth = state.depth.min(self.max_depth);
+            Some(state)
+        }
+    }
+
+    #[test]
+    fn test_models() {
+        let cmds = input_generator(INPUT).unwrap();
+
+        let direct = Submarine::run(Direct, &cmds).unwrap();
+        assert_eq!(
+            (direct.position(), direct.depth(), direct.aim()),
+            (15, 10, 0)
+        );
+
+        let clamped = Submarine::run(Clamped { max_depth: 50 }, &cmds).unwrap();
+        assert_eq!(clamped.depth(), 50);
+        assert_eq!(clamped.max_depth(), 50);
+    }
+
     #[test]
     fn test_part1() {
-        assert_eq!(solve_part1(input_generator(INPUT).unwrap().as_slice()), 150)
+        assert_eq!(
+            solve_part1(input_generator(INPUT).unwrap().as_slice()),
+            Some(150)
+        )
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(solve_part2(input_generator(INPUT).unwrap().as_slice()), 900)
+        assert_eq!(
+            solve_part2(input_generator(INPUT).unwrap().as_slice()),
+            Some(900)
+        )
+    }
+
+    #[test]
+    fn test_long_route() {
+        let cmds = input_generator("repeat 100000 { down 9 forward 9 }").unwrap();
+        assert_eq!(solve_part1(&cmds), Some(900_000 * 900_000));
+        // the aim grows by 9 before each move, so the depth is 81 * (1 + 2 + ... + 100000)
+        assert_eq!(solve_part2(&cmds), Some(900_000 * 81 * 5_000_050_000));
+
+        let cmds = input_generator("repeat 3 { down 2147483647 forward 2147483647 }").unwrap();
+        assert_eq!(solve_part2(&cmds), None);
+        let mut submarine = Submarine::new(Aim);
+        assert!(cmds
+            .iter()
+            .map(|cmd| submarine.apply(cmd))
+            .any(|s| s.is_none()));
+        assert_eq!(submarine.trajectory().len(), 4);
     }
 }
@@ -1,94 +1,446 @@
 use super::*;
-use std::collections::HashMap;
+use crate::{bignum::BigNumber, solution::Maybe};
+use std::{borrow::Cow, ops::Range};
 
-#[aoc(day3, part1)]
-pub fn solve_part1(input: &str) -> u32 {
-    let map = input.lines().fold(HashMap::new(), |mut map, num| {
-        for (i, c) in num.chars().rev().enumerate() {
-            match c {
-                '0' => map.entry(i).or_insert((0, 0)).0 += 1,
-                '1' => map.entry(i).or_insert((0, 0)).1 += 1,
-                _ => panic!("unexpected input"),
-            }
+/// Binary readings of equal width, packed into 64 bit words per reading.
+///
+/// Columns are counted from the left, so column 0 is the most significant bit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiagnosticReport {
+    width: usize,
+    words: usize,
+    bits: Vec<u64>,
+}
+
+impl DiagnosticReport {
+    fn new(width: usize) -> Self {
+        Self {
+            width,
+            // a reading without columns still takes a word, so that the readings can be counted
+            words: width.div_ceil(64).max(1),
+            bits: Vec::new(),
         }
-        map
-    });
-
-    let (gamma, epsilon) = map
-        .into_iter()
-        .map(|(i, (zero, one))| (i, zero < one))
-        .fold((0_u32, 0_u32), |(gamma, eps), (i, n)| {
-            if n {
-                (gamma + 2_u32.pow(i as u32), eps)
-            } else {
-                (gamma, eps + 2_u32.pow(i as u32))
-            }
+    }
+
+    // adds a reading with `bit(column)` in each column
+    fn push_with(&mut self, bit: impl Fn(usize) -> bool) {
+        let mut packed = vec![0; self.words];
+        for column in (0..self.width).filter(|&c| bit(c)) {
+            let i = self.width - 1 - column;
+            packed[i / 64] |= 1 << (i % 64);
+        }
+        self.bits.extend(packed);
+    }
+
+    fn push(&mut self, reading: &str) {
+        self.push_with(|column| reading.as_bytes()[column] == b'1')
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn len(&self) -> usize {
+        self.bits.len() / self.words
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    /// The packed bits of reading `i`, least significant word first.
+    pub fn reading(&self, i: usize) -> &[u64] {
+        &self.bits[i * self.words..(i + 1) * self.words]
+    }
+
+    pub fn bit(&self, i: usize, column: usize) -> bool {
+        let bit = self.width - 1 - column;
+        self.reading(i)[bit / 64] >> (bit % 64) & 1 == 1
+    }
+
+    /// Number of readings with a one in `column`.
+    pub fn ones(&self, column: usize) -> usize {
+        (0..self.len()).filter(|&i| self.bit(i, column)).count()
+    }
+
+    pub fn value(&self, i: usize) -> BigNumber {
+        BigNumber::from_words(self.reading(i).to_vec())
+    }
+
+    /// A report of the same readings with only the given `columns`, in that order.
+    ///
+    /// # Panics
+    ///
+    /// If one of the columns is not below the width of the report.
+    pub fn reorder(&self, columns: &[usize]) -> DiagnosticReport {
+        let mut report = DiagnosticReport::new(columns.len());
+        for i in 0..self.len() {
+            report.push_with(|column| self.bit(i, columns[column]));
+        }
+        report
+    }
+
+    /// The indices of the readings sorted by their value.
+    ///
+    /// Readings that agree on their first columns end up next to each other, and within such a
+    /// range the ones with a zero in the next column come first.
+    pub fn sorted(&self) -> Vec<usize> {
+        let mut order: Vec<_> = (0..self.len()).collect();
+        order.sort_by(|&a, &b| {
+            self.reading(a)
+                .iter()
+                .rev()
+                .cmp(self.reading(b).iter().rev())
         });
+        order
+    }
 
-    gamma * epsilon
-}
+    /// Filters the readings by each column that `rating` scans in turn, until only one is left.
+    ///
+    /// The readings are sorted by their bits in scan order, so each column only splits the
+    /// remaining range in two and apart from sorting this takes a binary search per column.
+    /// Without any columns to scan all readings are kept, and the first one is chosen.
+    ///
+    /// # Panics
+    ///
+    /// If `rating` scans a column that is not below the width of the report.
+    pub fn select(&self, rating: &Rating) -> Selection {
+        let columns = rating.order.columns(self.width);
+        let report = match rating.order {
+            ScanOrder::MsbFirst => Cow::Borrowed(self),
+            _ => Cow::Owned(self.reorder(&columns)),
+        };
 
-#[aoc(day3, part2)]
-pub fn solve_part2(input: &str) -> u32 {
-    let nums: Vec<_> = input.lines().collect();
-
-    let most_common_bit = |nums: &[&str], i| {
-        let mut count = 0;
-        for num in nums {
-            if num.chars().nth(i).unwrap() == '0' {
-                count -= 1;
-            } else {
-                count += 1;
+        let order = report.sorted();
+        let mut range = 0..order.len();
+        let mut ranges = Vec::new();
+
+        for column in 0..columns.len() {
+            if range.len() <= 1 {
+                break;
             }
+            ranges.push(range.clone());
+            let split =
+                range.start + order[range.clone()].partition_point(|&i| !report.bit(i, column));
+            range = if rating.criterion.keep(range.end - split, range.len()) {
+                split..range.end
+            } else {
+                range.start..split
+            };
         }
-        if count >= 0 {
-            '1'
-        } else {
-            '0'
+        ranges.push(range);
+
+        Selection {
+            columns,
+            order,
+            ranges,
+        }
+    }
+
+    /// The value of the reading that `rating` selects, `None` if it filtered out every reading.
+    ///
+    /// # Panics
+    ///
+    /// If `rating` scans a column that is not below the width of the report.
+    pub fn rating(&self, rating: &Rating) -> Option<BigNumber> {
+        self.select(rating).reading().map(|i| self.value(i))
+    }
+}
+
+/// Which bit a column has to have for a reading to be kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitCriterion {
+    /// The bit most of the remaining readings have, `tie` when there are as many zeros as ones.
+    MostCommon { tie: bool },
+    /// The bit the fewest of the remaining readings have, `tie` when there are as many zeros as
+    /// ones.
+    LeastCommon { tie: bool },
+}
+
+impl BitCriterion {
+    /// The bit to keep when `ones` of `len` remaining readings have a one.
+    pub fn keep(self, ones: usize, len: usize) -> bool {
+        let zeros = len - ones;
+        match self {
+            BitCriterion::MostCommon { tie } if ones == zeros => tie,
+            BitCriterion::LeastCommon { tie } if ones == zeros => tie,
+            BitCriterion::MostCommon { .. } => ones > zeros,
+            BitCriterion::LeastCommon { .. } => ones < zeros,
+        }
+    }
+}
+
+/// The order in which the columns are looked at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanOrder {
+    /// From the left, the most significant bit first.
+    MsbFirst,
+    /// From the right, the least significant bit first.
+    LsbFirst,
+    /// Only these columns, in this order.
+    Columns(Vec<usize>),
+}
+
+impl ScanOrder {
+    pub fn columns(&self, width: usize) -> Vec<usize> {
+        match self {
+            ScanOrder::MsbFirst => (0..width).collect(),
+            ScanOrder::LsbFirst => (0..width).rev().collect(),
+            ScanOrder::Columns(columns) => columns.clone(),
         }
+    }
+}
+
+/// A kind of rating, chosen by filtering the readings on `criterion` in `order`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rating {
+    pub criterion: BitCriterion,
+    pub order: ScanOrder,
+}
+
+impl Rating {
+    pub const OXYGEN_GENERATOR: Rating = Rating {
+        criterion: BitCriterion::MostCommon { tie: true },
+        order: ScanOrder::MsbFirst,
     };
 
-    let least_common_bit = |nums: &[&str], i| match most_common_bit(nums, i) {
-        '0' => '1',
-        '1' => '0',
-        _ => unreachable!(),
+    pub const CO2_SCRUBBER: Rating = Rating {
+        criterion: BitCriterion::LeastCommon { tie: false },
+        order: ScanOrder::MsbFirst,
     };
+}
 
-    let (mut o2, mut co2) = (nums.clone(), nums.clone());
-    let mut bit = 0;
+/// How a rating was chosen from a [`DiagnosticReport`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selection {
+    /// The columns in the order they were looked at.
+    pub columns: Vec<usize>,
+    /// The indices of the readings in the order they were filtered in.
+    pub order: Vec<usize>,
+    /// The range of `order` that was left before each of `columns` was looked at, followed by the
+    /// one that was left at the end.
+    pub ranges: Vec<Range<usize>>,
+}
 
-    while o2.len() > 1 {
-        let mcb = most_common_bit(&o2.clone(), bit);
-        o2.retain(|num| num.chars().nth(bit).unwrap() == mcb);
-        bit += 1;
+impl Selection {
+    /// The index of the chosen reading, `None` if the filter left no readings.
+    pub fn reading(&self) -> Option<usize> {
+        let range = self.ranges.last()?;
+        (!range.is_empty()).then(|| self.order[range.start])
     }
+}
+
+#[aoc_generator(day3)]
+pub fn input_generator(input: &str) -> Result<DiagnosticReport, ParseError> {
+    use crate::parse::{lines, parse_all};
+    use nom::{bytes::complete::is_a, error::context};
 
-    bit = 0;
-    while co2.len() > 1 {
-        let lcb = least_common_bit(&co2.clone(), bit);
-        co2.retain(|num| num.chars().nth(bit).unwrap() == lcb);
-        bit += 1;
+    let readings = parse_all(3, input, lines(context("a binary number", is_a("01"))))?;
+
+    let mut report = DiagnosticReport::new(readings[0].len());
+    for reading in readings {
+        if reading.len() != report.width {
+            let at = &reading[report.width.min(reading.len())..];
+            let expected = format!("a binary number with {} digits", report.width);
+            return Err(ParseError::new(3, input, at, expected));
+        }
+        report.push(reading);
     }
 
-    u32::from_str_radix(o2[0], 2).unwrap() * u32::from_str_radix(co2[0], 2).unwrap()
+    Ok(report)
+}
+
+#[aoc(day3, part1)]
+pub fn solve_part1(report: &DiagnosticReport) -> BigNumber {
+    let mut gamma = vec![0; report.words];
+    let mut epsilon = vec![0; report.words];
+
+    for column in 0..report.width {
+        let bit = report.width - 1 - column;
+        let most_common = report.ones(column) * 2 > report.len();
+        let word = if most_common {
+            &mut gamma
+        } else {
+            &mut epsilon
+        };
+        word[bit / 64] |= 1 << (bit % 64);
+    }
+
+    &BigNumber::from_words(gamma) * &BigNumber::from_words(epsilon)
+}
+
+#[aoc(day3, part2)]
+pub fn solve_part2(report: &DiagnosticReport) -> Option<BigNumber> {
+    let o2 = report.rating(&Rating::OXYGEN_GENERATOR)?;
+    let co2 = report.rating(&Rating::CO2_SCRUBBER)?;
+
+    Some(&o2 * &co2)
+}
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    const DAY: u8 = 3;
+
+    type Input = DiagnosticReport;
+    type Output1 = BigNumber;
+    type Output2 = Maybe<BigNumber>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        solve_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        Maybe(solve_part2(input))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_util::Rng;
+
+    static INPUT: &str =
+        "00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010";
+
+    #[test]
+    fn test_parse() {
+        let report = input_generator(INPUT).unwrap();
+        assert_eq!((report.len(), report.width()), (12, 5));
+        assert_eq!(report.reading(1), [0b11110]);
+        assert_eq!(report.ones(0), 7);
+
+        let err = input_generator("00100\n1111\n10110").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 5));
+        let err = input_generator("00100\n111101").unwrap_err();
+        assert_eq!((err.line, err.column, err.found.as_str()), (2, 6, "`1`"));
+    }
+
+    #[test]
+    fn test_select() {
+        let report = input_generator(INPUT).unwrap();
+
+        let o2 = report.select(&Rating::OXYGEN_GENERATOR);
+        let values: Vec<_> = o2.order.iter().map(|&i| report.reading(i)[0]).collect();
+        assert!(values.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(o2.ranges, [0..12, 5..12, 5..9, 6..9, 7..9, 8..9]);
+        assert_eq!(o2.reading(), Some(3));
+
+        let co2 = report.select(&Rating::CO2_SCRUBBER);
+        assert_eq!(
+            co2.ranges.iter().map(|r| r.len()).collect::<Vec<_>>(),
+            [12, 5, 2, 1]
+        );
+        assert_eq!(report.reading(co2.reading().unwrap()), [0b01010]);
+
+        // every reading has the same first bit, so there is no least common one
+        let report = input_generator("00\n01").unwrap();
+        assert_eq!(report.rating(&Rating::CO2_SCRUBBER), None);
+        assert_eq!(solve_part2(&report), None);
+    }
+
+    // filters the readings the slow way, one column at a time
+    fn brute_force(report: &DiagnosticReport, rating: &Rating) -> Option<usize> {
+        let mut left: Vec<_> = (0..report.len()).collect();
+        for column in rating.order.columns(report.width()) {
+            if left.len() <= 1 {
+                break;
+            }
+            let ones = left.iter().filter(|&&i| report.bit(i, column)).count();
+            let bit = rating.criterion.keep(ones, left.len());
+            left.retain(|&i| report.bit(i, column) == bit);
+        }
+        left.first().copied()
+    }
+
+    #[test]
+    fn test_ratings() {
+        let mut rng = Rng::new(7);
+        let mut readings = Vec::new();
+        for _ in 0..200 {
+            let reading: String = (0..70)
+                .map(|_| if rng.range(0..2) == 1 { '1' } else { '0' })
+                .collect();
+            readings.push(reading);
+        }
+        let random = input_generator(&readings.join("\n")).unwrap();
+        let example = input_generator(INPUT).unwrap();
+
+        let criteria = [
+            BitCriterion::MostCommon { tie: true },
+            BitCriterion::MostCommon { tie: false },
+            BitCriterion::LeastCommon { tie: true },
+            BitCriterion::LeastCommon { tie: false },
+        ];
+        let orders = [
+            ScanOrder::MsbFirst,
+            ScanOrder::LsbFirst,
+            ScanOrder::Columns(vec![4, 0, 2, 1, 3]),
+            ScanOrder::Columns(vec![3, 1]),
+            ScanOrder::Columns(vec![]),
+        ];
+        for report in [&example, &random] {
+            for criterion in criteria {
+                for order in &orders {
+                    let rating = Rating {
+                        criterion,
+                        order: order.clone(),
+                    };
+                    assert_eq!(
+                        report.select(&rating).reading(),
+                        brute_force(report, &rating)
+                    );
+                }
+            }
+        }
+
+        // the least significant bits of the example pick other readings
+        let lsb = Rating {
+            criterion: BitCriterion::MostCommon { tie: true },
+            order: ScanOrder::LsbFirst,
+        };
+        assert_eq!(example.rating(&lsb), Some(BigNumber::from(0b11110)));
+
+        let none = Rating {
+            criterion: BitCriterion::MostCommon { tie: true },
+            order: ScanOrder::Columns(vec![]),
+        };
+        let selection = example.select(&none);
+        assert_eq!(selection.ranges.len(), 1);
+        assert_eq!(selection.ranges[0], 0..12);
+        assert_eq!(example.rating(&none), Some(BigNumber::from(0b00100)));
+    }
+
+    #[test]
+    fn test_wide_readings() {
+        let high = format!("1{}", "0".repeat(129));
+        let low = format!("0{}", "1".repeat(129));
+        let report = input_generator(&[high.as_str(), &high, &low].join("\n")).unwrap();
+
+        // 2^129 * (2^129 - 1)
+        let product =
+            "463168356949264781694283940034751631412399373928720379230903586816788982136832";
+        assert_eq!(solve_part1(&report).to_string(), product);
+        assert_eq!(solve_part2(&report).unwrap().to_string(), product);
+    }
 
     #[test]
     fn test_part1() {
         assert_eq!(
-            solve_part1("00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010"),
-            198
+            solve_part1(&input_generator(INPUT).unwrap()),
+            BigNumber::from(198)
         )
     }
+
     #[test]
     fn test_part2() {
         assert_eq!(
-            solve_part2("00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010"),
-            230
+            solve_part2(&input_generator(INPUT).unwrap()),
+            Some(BigNumber::from(230))
         )
     }
 }
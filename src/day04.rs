@@ -1,149 +1,101 @@
 use super::*;
+use crate::{
+    bingo::{bingo, Bingo, WinConditions},
+    solution::Maybe,
+};
 
-const BOARD_SIZE: usize = 5;
+#[aoc_generator(day4)]
+pub fn input_generator(input: &str) -> Result<Bingo, ParseError> {
+    use crate::parse::parse_all;
+
+    parse_all(4, input, bingo)
+}
 
 #[aoc(day4, part1)]
-pub fn solve_part1(input: &str) -> u32 {
-    let mut lines = input.lines();
-
-    // parse "random" numbers
-    let mut nums = lines
-        .next()
-        .unwrap()
-        .split(',')
-        .map(|c| u32::from_str_radix(c, 10).unwrap());
-
-    // parse all the boards
-    let mut boards: Vec<_> = lines
-        .map(|line| line.split_whitespace())
-        .flatten()
-        .map(|n| Some(u32::from_str_radix(n, 10).unwrap()))
-        .collect::<Vec<_>>()
-        .chunks_exact(25)
-        .map(|s| s.to_vec())
-        .collect();
-
-    // check wether a given board fulfills winning condition (marked row or col)
-    let has_won = |board: &[Option<u32>]| {
-        (0..BOARD_SIZE).any(|i| {
-            board[BOARD_SIZE * i..BOARD_SIZE * (i + 1)]
-                .iter()
-                .all(Option::is_none)
-                || board[i..=(BOARD_SIZE - 1) * BOARD_SIZE + i]
-                    .iter()
-                    .step_by(BOARD_SIZE)
-                    .all(Option::is_none)
-        })
-    };
-
-    // loop through the numbers
-    let (board, num) = 'outer: loop {
-        let num = nums.next().unwrap();
-
-        // mark the number on each board if it exists and check if the board has won
-        for board in boards.iter_mut() {
-            match board.iter().position(|&n| n == Some(num)) {
-                Some(i) => {
-                    board[i] = None;
-                    if has_won(board) {
-                        break 'outer (board, num);
-                    }
-                }
-                None => {
-                    continue;
-                }
-            }
-        }
-    };
-
-    // sum all unmarked numbers on the winning board and multiply by last number
-    board.iter().fold(0, |sum, n| match n {
-        Some(n) => sum + n,
-        None => sum,
-    }) * num
+pub fn solve_part1(game: &Bingo) -> Option<u32> {
+    let timeline = game.timeline(WinConditions::STANDARD);
+    timeline.first().map(|(_, win)| win.score)
 }
 
 #[aoc(day4, part2)]
-pub fn solve_part2(input: &str) -> u32 {
-    let mut lines = input.lines();
-
-    // parse "random" numbers
-    let mut nums = lines
-        .next()
-        .unwrap()
-        .split(',')
-        .map(|c| u32::from_str_radix(c, 10).unwrap());
-
-    // parse all the boards
-    let mut boards: Vec<_> = lines
-        .map(|line| line.split_whitespace())
-        .flatten()
-        .map(|n| Some(u32::from_str_radix(n, 10).unwrap()))
-        .collect::<Vec<_>>()
-        .chunks_exact(25)
-        .map(|s| s.to_vec())
-        .collect();
-
-    // check wether a given board fulfills winning condition (marked row or col)
-    let has_won = |board: &[Option<u32>]| {
-        (0..BOARD_SIZE).any(|i| {
-            board[BOARD_SIZE * i..BOARD_SIZE * (i + 1)]
-                .iter()
-                .all(Option::is_none)
-                || board[i..=(BOARD_SIZE - 1) * BOARD_SIZE + i]
-                    .iter()
-                    .step_by(BOARD_SIZE)
-                    .all(Option::is_none)
-        })
-    };
-
-    let mut board_idx = 0;
-    let mut num_idx = 0;
-
-    'outer: for (j, board) in boards.iter_mut().enumerate() {
-        for (i, num) in nums.clone().enumerate() {
-            match board.iter().position(|&n| n == Some(num)) {
-                Some(p) => {
-                    board[p] = None;
-                    if has_won(board) {
-                        if i > num_idx {
-                            num_idx = i;
-                            board_idx = j;
-                        }
-                        continue 'outer;
-                    }
-                }
-                None => {
-                    continue;
-                }
-            }
-        }
+pub fn solve_part2(game: &Bingo) -> Option<u32> {
+    let timeline = game.timeline(WinConditions::STANDARD);
+    timeline.last().map(|(_, win)| win.score)
+}
+
+pub struct Day04;
+
+impl Solution for Day04 {
+    const DAY: u8 = 4;
+
+    type Input = Bingo;
+    type Output1 = Maybe<u32>;
+    type Output2 = Maybe<u32>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        Maybe(solve_part1(input))
     }
 
-    // sum all unmarked numbers on the winning board and multiply by last number
-    boards[board_idx].iter().fold(0, |sum, n| match n {
-        Some(n) => sum + n,
-        None => sum,
-    }) * nums.nth(num_idx).unwrap()
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        Maybe(solve_part2(input))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::bingo::{Line, Win};
+
+    static INPUT: &str = "7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1\n\n22 13 17 11  0\n 8  2 23  4 24\n21  9 14 16  7\n 6 10  3 18  5\n 1 12 20 15 19\n\n 3 15  0  2 22\n 9 18 13 17  5\n19  8  7 25 23\n20 11 10 24  4\n14 21 16 12  6\n\n14 21 17 24  4\n10 16 15  9 19\n18  8 23 26 20\n22 11 13  6  5\n 2  0 12  3  7";
 
     #[test]
-    fn test_part1() {
+    fn test_timeline() {
+        let game = input_generator(INPUT).unwrap();
+        let timeline = game.timeline(WinConditions::STANDARD);
+
+        let ranking: Vec<_> = timeline.ranking().iter().map(|&(i, _)| i).collect();
+        assert_eq!(ranking, [2, 0, 1]);
         assert_eq!(
-            solve_part1("7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1\n\n22 13 17 11  0\n 8  2 23  4 24\n21  9 14 16  7\n 6 10  3 18  5\n 1 12 20 15 19\n\n 3 15  0  2 22\n 9 18 13 17  5\n19  8  7 25 23\n20 11 10 24  4\n14 21 16 12  6\n\n14 21 17 24  4\n10 16 15  9 19\n18  8 23 26 20\n22 11 13  6  5\n 2  0 12  3  7"),
-            4512
-        )
+            timeline.board(2),
+            Some(&Win {
+                draw: 11,
+                number: 24,
+                line: Line::Row(0),
+                score: 4512
+            })
+        );
+        let (third, win) = timeline.nth(2).unwrap();
+        assert_eq!((third, win.draw, win.number, win.score), (1, 14, 13, 1924));
+        assert_eq!(timeline.nth(3), None);
+
+        // with fewer numbers drawn the last board never wins
+        let short = Bingo {
+            numbers: game.numbers[..14].to_vec(),
+            ..game.clone()
+        };
+        let timeline = short.timeline(WinConditions::STANDARD);
+        assert_eq!(timeline.never_won().collect::<Vec<_>>(), [1]);
+        assert_eq!(timeline.last().map(|(i, _)| i), Some(0));
+
+        // every number on the boards is drawn eventually
+        let ranking = game.timeline(WinConditions::FULL_CARD).ranking();
+        assert_eq!(ranking.len(), 3);
+        assert!(ranking
+            .iter()
+            .all(|(_, win)| win.line == Line::FullCard && win.score == 0));
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(solve_part1(&input_generator(INPUT).unwrap()), Some(4512))
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(
-            solve_part2("7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1\n\n22 13 17 11  0\n 8  2 23  4 24\n21  9 14 16  7\n 6 10  3 18  5\n 1 12 20 15 19\n\n 3 15  0  2 22\n 9 18 13 17  5\n19  8  7 25 23\n20 11 10 24  4\n14 21 16 12  6\n\n14 21 17 24  4\n10 16 15  9 19\n18  8 23 26 20\n22 11 13  6  5\n 2  0 12  3  7"),
-            1924
-        )
+        assert_eq!(solve_part2(&input_generator(INPUT).unwrap()), Some(1924))
     }
 }
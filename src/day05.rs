@@ -51,27 +51,20 @@ impl Line {
 }
 
 #[aoc_generator(day5)]
-pub fn input_generator(input: &str) -> Result<Vec<Line>, nom::Err<()>> {
-    use nom::{
-        bytes::complete::tag,
-        character::complete::{char, line_ending, u32},
-        multi::separated_list1,
-        sequence::separated_pair,
-        Parser,
-    };
+pub fn input_generator(input: &str) -> Result<Vec<Line>, ParseError> {
+    use crate::parse::{coord_pair, lines, parse_all};
+    use nom::Parser;
 
-    let coord = |i| {
-        separated_pair(u32, char(','), u32)
-            .map(|(x, y)| Point {
-                x: x as usize,
-                y: y as usize,
-            })
-            .parse(i)
+    let point = |(x, y): (u32, u32)| Point {
+        x: x as usize,
+        y: y as usize,
     };
-    let line = separated_pair(coord, tag(" -> "), coord).map(|(start, end)| Line { start, end });
-    let mut parse = separated_list1(line_ending, line);
+    let line = coord_pair.map(|(start, end)| Line {
+        start: point(start),
+        end: point(end),
+    });
 
-    parse.parse(input).map(|(_, p)| p)
+    parse_all(5, input, lines(line))
 }
 
 fn calculate_intersection(mut lines: Vec<Line>, filter: impl FnMut(&Line) -> bool) -> usize {
@@ -118,6 +111,28 @@ pub fn solve_part2(lines: &[Line]) -> usize {
     calculate_intersection(lines.to_vec(), |_| true)
 }
 
+pub struct Day05;
+
+impl Solution for Day05 {
+    const DAY: u8 = 5;
+
+    type Input = Vec<Line>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        solve_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        solve_part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
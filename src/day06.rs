@@ -6,7 +6,7 @@ pub struct Lanternfish {
 }
 
 fn simulate_lanternfish(fish: Vec<Lanternfish>, days: usize) -> usize {
-    let mut fish_counts = vec![0; 9];
+    let mut fish_counts = [0; 9];
 
     for f in fish {
         fish_counts[f.timer] += 1;
@@ -21,13 +21,14 @@ fn simulate_lanternfish(fish: Vec<Lanternfish>, days: usize) -> usize {
 }
 
 #[aoc_generator(day6)]
-pub fn input_generator(input: &str) -> Vec<Lanternfish> {
-    input
-        .split(',')
-        .map(|timer| Lanternfish {
-            timer: timer.parse().unwrap(),
-        })
-        .collect()
+pub fn input_generator(input: &str) -> Result<Vec<Lanternfish>, ParseError> {
+    use crate::parse::{comma_list, parse_all};
+    use nom::{character::complete::u8, combinator::verify, error::context, Parser};
+
+    let timer = context("a timer between 0 and 8", verify(u8, |t| *t <= 8))
+        .map(|t| Lanternfish { timer: t as usize });
+
+    parse_all(6, input, comma_list(timer))
 }
 
 #[aoc(day6 part1)]
@@ -40,25 +41,53 @@ pub fn solve_part2(fish: &[Lanternfish]) -> usize {
     simulate_lanternfish(fish.to_vec(), 256)
 }
 
+pub struct Day06;
+
+impl Solution for Day06 {
+    const DAY: u8 = 6;
+
+    type Input = Vec<Lanternfish>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        solve_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        solve_part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_simulation() {
-        let fish = input_generator("3,4,3,1,2");
+        let fish = input_generator("3,4,3,1,2").unwrap();
         assert_eq!(simulate_lanternfish(fish, 18), 26)
     }
 
+    #[test]
+    fn test_trailing_newline() {
+        let fish = input_generator("3,4,3,1,2\n").unwrap();
+        assert_eq!(fish.len(), 5)
+    }
+
     #[test]
     fn test_part1() {
-        let fish = input_generator("3,4,3,1,2");
+        let fish = input_generator("3,4,3,1,2").unwrap();
         assert_eq!(solve_part1(&fish), 5934)
     }
 
     #[test]
     fn test_part2() {
-        let fish = input_generator("3,4,3,1,2");
+        let fish = input_generator("3,4,3,1,2").unwrap();
         assert_eq!(solve_part2(&fish), 26984457539)
     }
 }
@@ -1,20 +1,16 @@
 use super::*;
 
 #[aoc_generator(day7)]
-pub fn input_generator(input: &str) -> Result<Vec<i32>, nom::Err<()>> {
-    use nom::{
-        character::complete::{char, i32},
-        multi::separated_list1,
-        Parser,
-    };
-
-    let mut parse = separated_list1(char(','), i32);
-    parse.parse(input).map(|(_, p)| p)
+pub fn input_generator(input: &str) -> Result<Vec<i32>, ParseError> {
+    use crate::parse::{comma_list, parse_all};
+    use nom::{character::complete::i32, error::context};
+
+    parse_all(7, input, comma_list(context("a position", i32)))
 }
 
 fn find_min(crabs: &[i32], fuel: impl Fn(i32) -> i32) -> i32 {
     (crabs.iter().min().copied().unwrap()..=crabs.iter().max().copied().unwrap())
-        .map(|p| fuel(p))
+        .map(fuel)
         .min()
         .unwrap()
 }
@@ -39,6 +35,28 @@ pub fn solve_part2(crabs: &[i32]) -> i32 {
     })
 }
 
+pub struct Day07;
+
+impl Solution for Day07 {
+    const DAY: u8 = 7;
+
+    type Input = Vec<i32>;
+    type Output1 = i32;
+    type Output2 = i32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        solve_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        solve_part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -22,24 +22,60 @@ pub struct Signal {
 }
 
 #[aoc_generator(day8)]
-pub fn input_generator(input: &str) -> Vec<Signal> {
-    input
-        .lines()
-        .map(|l| {
-            let (input, output) = l.split_once('|').unwrap();
-            let input: Vec<_> = input
-                .split_whitespace()
-                .map(|signal| signal.to_owned())
-                .collect();
-
-            let output: Vec<_> = output
-                .split_whitespace()
-                .map(|segments| segments.to_owned())
-                .collect();
-
-            Signal { input, output }
-        })
-        .collect()
+pub fn input_generator(input: &str) -> Result<Vec<Signal>, ParseError> {
+    use crate::parse::{lines, parse_all, IResult};
+    use nom::{
+        bytes::complete::{tag, take_while_m_n},
+        character::complete::{char, space0},
+        combinator::verify,
+        error::context,
+        multi::separated_list1,
+        sequence::{separated_pair, terminated},
+        Parser,
+    };
+
+    let pattern = |i| -> IResult<String> {
+        context(
+            "a pattern of segments `a` to `g`",
+            verify(
+                take_while_m_n(2, 7, |c| ('a'..='g').contains(&c)),
+                |p: &str| {
+                    p.bytes()
+                        .enumerate()
+                        .all(|(i, b)| !p[..i].contains(b as char))
+                },
+            ),
+        )
+        .map(str::to_owned)
+        .parse(i)
+    };
+    let patterns = || separated_list1(char(' '), pattern);
+    // the ten digits, the ones with a unique number of segments are needed to tell the others
+    // apart
+    let unique = context(
+        "ten distinct patterns including ones of 2, 3, 4 and 7 segments",
+        verify(patterns(), |patterns: &[String]| {
+            let mut sets: Vec<_> = patterns.iter().map(|p| segments(p)).collect();
+            sets.sort_by_key(|s| s.bits());
+            sets.dedup();
+            sets.len() == 10
+                && [2, 3, 4, 7]
+                    .iter()
+                    .all(|&l| patterns.iter().any(|p| p.len() == l))
+        }),
+    );
+    let output = context(
+        "four output digits",
+        verify(patterns(), |patterns: &[String]| patterns.len() == 4),
+    );
+
+    let signal = terminated(
+        separated_pair(unique, context("` | `", tag(" | ")), output),
+        space0,
+    )
+    .map(|(input, output)| Signal { input, output });
+
+    parse_all(8, input, lines(signal))
 }
 
 #[aoc(day8 part1)]
@@ -128,6 +164,28 @@ pub fn solve_part2(signals: &[Signal]) -> u32 {
     sum
 }
 
+pub struct Day08;
+
+impl Solution for Day08 {
+    const DAY: u8 = 8;
+
+    type Input = Vec<Signal>;
+    type Output1 = usize;
+    type Output2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        solve_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        solve_part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -136,11 +194,43 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(solve_part1(&input_generator(INPUT)), 26)
+        assert_eq!(solve_part1(&input_generator(INPUT).unwrap()), 26)
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(solve_part2(&input_generator(INPUT)), 61229)
+        assert_eq!(solve_part2(&input_generator(INPUT).unwrap()), 61229)
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let line =
+            "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf";
+        assert_eq!(input_generator(line).unwrap().len(), 1);
+
+        let err = input_generator(&format!("{line}\nab abcd abcdefg | abcde")).unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.expected.as_str()),
+            (
+                2,
+                1,
+                "ten distinct patterns including ones of 2, 3, 4 and 7 segments"
+            )
+        );
+
+        // the pattern of 7 segments is missing, one of 6 segments appears twice
+        let twice = line.replace("acedgfb", "cdbgef");
+        let err = input_generator(&twice).unwrap_err();
+        assert_eq!((err.line, err.column), (1, 1));
+
+        let err = input_generator(&format!("{line} ab")).unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.expected.as_str()),
+            (1, 62, "four output digits")
+        );
+
+        // a segment can't be lit twice, the list of patterns ends before `daa`
+        let err = input_generator(&line.replace("dab", "daa")).unwrap_err();
+        assert_eq!((err.line, err.column), (1, 1));
     }
 }
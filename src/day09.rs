@@ -1,97 +1,46 @@
 use super::*;
+use crate::grid::Grid;
 
-fn explore_basin((r, c): (usize, usize), map: &mut [Vec<bool>]) -> usize {
-    let mut to_visit = vec![(r, c)];
+fn explore_basin(start: (usize, usize), map: &mut Grid<bool>) -> usize {
+    let mut to_visit = vec![start];
     let mut size = 0;
 
-    while !to_visit.is_empty() {
-        let (r, c) = to_visit.pop().unwrap();
-
-        if !map[r][c] {
+    while let Some(pos) = to_visit.pop() {
+        if !map[pos] {
             continue;
         }
 
         size += 1;
-        map[r][c] = false;
-
-        if let Some(true) = r.checked_sub(1).map(|r| map[r][c]) {
-            to_visit.push((r - 1, c))
-        }
-
-        if let Some(true) = map.get(r + 1).map(|r| r[c]) {
-            to_visit.push((r + 1, c))
-        }
-
-        if let Some(true) = c.checked_sub(1).map(|c| map[r][c]) {
-            to_visit.push((r, c - 1))
-        }
+        map[pos] = false;
 
-        if let Some(true) = map[r].get(c + 1).copied() {
-            to_visit.push((r, c + 1))
-        }
+        to_visit.extend(map.neighbors4(pos).filter(|&p| map[p]));
     }
 
     size
 }
 
 #[aoc_generator(day9)]
-pub fn input_generator(input: &str) -> Result<Vec<Vec<u32>>, nom::Err<()>> {
-    use nom::{
-        bytes::complete::take,
-        character::complete::{line_ending, u32},
-        multi::{many1, separated_list1},
-        Parser,
-    };
-    let height = take(1u8).and_then(u32);
-    let row = many1(height);
-    let mut parser = separated_list1(line_ending, row);
-
-    parser.parse(input).map(|(_, i)| i)
-}
-
-fn low_points(map: &[Vec<u32>]) -> Vec<(usize, usize)> {
-    let rows = map.len();
-    let cols = map[0].len();
-
-    let mut low_points = Vec::new();
+pub fn input_generator(input: &str) -> Result<Grid<u32>, ParseError> {
+    use crate::{grid::grid, parse::parse_all};
 
-    for r in 0..rows {
-        for c in 0..cols {
-            let current = map[r][c];
-
-            let top = r.checked_sub(1).map(|r| map[r][c]);
-            let bot = map.get(r + 1).map(|r| r[c]);
-            let left = c.checked_sub(1).map(|c| map[r][c]);
-            let right = map[r].get(c + 1).copied();
-
-            if top.map_or(true, |top| top > current)
-                && bot.map_or(true, |bot| bot > current)
-                && left.map_or(true, |left| left > current)
-                && right.map_or(true, |right| right > current)
-            {
-                low_points.push((r, c));
-            }
-        }
-    }
+    parse_all(9, input, grid)
+}
 
-    low_points
+fn low_points(map: &Grid<u32>) -> Vec<(usize, usize)> {
+    map.indexed_iter()
+        .filter(|&(pos, current)| map.neighbors4(pos).all(|p| map[p] > *current))
+        .map(|(pos, _)| pos)
+        .collect()
 }
 
 #[aoc(day9 part1)]
-pub fn solve_part1(map: &[Vec<u32>]) -> u32 {
-    low_points(map).iter().map(|(r, c)| map[*r][*c] + 1).sum()
+pub fn solve_part1(map: &Grid<u32>) -> u32 {
+    low_points(map).iter().map(|&pos| map[pos] + 1).sum()
 }
 
 #[aoc(day9 part2)]
-pub fn solve_part2(map: &[Vec<u32>]) -> usize {
-    let mut simple_map: Vec<Vec<_>> = map
-        .iter()
-        .map(|row| {
-            row.iter()
-                .map(|&v| if v != 9 { true } else { false })
-                .collect()
-        })
-        .collect();
+pub fn solve_part2(map: &Grid<u32>) -> usize {
+    let mut simple_map = map.map(|&v| v != 9);
 
     let mut basin_sizes: Vec<_> = low_points(map)
         .iter()
@@ -102,6 +51,28 @@ pub fn solve_part2(map: &[Vec<u32>]) -> usize {
     basin_sizes.iter().rev().take(3).product()
 }
 
+pub struct Day09;
+
+impl Solution for Day09 {
+    const DAY: u8 = 9;
+
+    type Input = Grid<u32>;
+    type Output1 = u32;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        solve_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        solve_part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
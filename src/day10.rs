@@ -1,9 +1,21 @@
 use super::*;
+use crate::solution::Maybe;
+
+#[aoc_generator(day10)]
+pub fn input_generator(input: &str) -> Result<Vec<String>, ParseError> {
+    use crate::parse::{lines, parse_all};
+    use nom::{bytes::complete::take_while1, error::context, Parser};
+
+    let line =
+        context("one of `()[]{}<>`", take_while1(|c| "()[]{}<>".contains(c))).map(str::to_owned);
+
+    parse_all(10, input, lines(line))
+}
 
 #[aoc(day10, part1)]
-pub fn solve_part1(input: &str) -> u32 {
-    input
-        .lines()
+pub fn solve_part1(lines: &[String]) -> u32 {
+    lines
+        .iter()
         .filter_map(|l| {
             let mut stack = Vec::new();
 
@@ -31,10 +43,11 @@ pub fn solve_part1(input: &str) -> u32 {
         .sum()
 }
 
+/// The middle score of the incomplete lines, `None` if all lines are corrupted.
 #[aoc(day10, part2)]
-pub fn solve_part2(input: &str) -> u64 {
-    let mut scores: Vec<_> = input
-        .lines()
+pub fn solve_part2(lines: &[String]) -> Option<u64> {
+    let mut scores: Vec<_> = lines
+        .iter()
         .filter_map(|l| {
             let mut stack = Vec::new();
 
@@ -68,7 +81,29 @@ pub fn solve_part2(input: &str) -> u64 {
         .collect();
 
     scores.sort();
-    scores[scores.len() / 2]
+    scores.get(scores.len() / 2).copied()
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+
+    type Input = Vec<String>;
+    type Output1 = u32;
+    type Output2 = Maybe<u64>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        solve_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        Maybe(solve_part2(input))
+    }
 }
 
 #[cfg(test)]
@@ -77,13 +112,28 @@ mod tests {
 
     static INPUT: &str = "[({(<(())[]>[[{[]{<()<>>\n[(()[<>])]({[<{<<[]>>(\n{([(<{}[<>[]}>{[]{[(<()>\n(((({<>}<{<{<>}{[]{[]{}\n[[<[([]))<([[{}[[()]]]\n[{[{({}]{}}([{[{{{}}([]\n{<[[]]>}<{[{[{[]{()[[[]\n[<(<(<(<{}))><([]([]()\n<{([([[(<>()){}]>(<<{{\n<{([{{}}[<[[[<>{}]]]>[]]";
 
+    #[test]
+    fn test_parse() {
+        let err = input_generator("[]\n(x)").unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.expected.as_str()),
+            (2, 2, "end of line")
+        );
+        let err = input_generator("()\nx").unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.expected.as_str()),
+            (2, 1, "one of `()[]{}<>`")
+        );
+    }
+
     #[test]
     fn test_part1() {
-        assert_eq!(solve_part1(INPUT), 26397)
+        assert_eq!(solve_part1(&input_generator(INPUT).unwrap()), 26397)
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(solve_part2(INPUT), 288957)
+        assert_eq!(solve_part2(&input_generator(INPUT).unwrap()), Some(288957));
+        assert_eq!(solve_part2(&input_generator("(]\n<)").unwrap()), None);
     }
 }
@@ -1,26 +1,16 @@
 use super::*;
-use ndarray::prelude::*;
-use std::ops::AddAssign;
+use crate::grid::Grid;
 
 #[aoc_generator(day11)]
-pub fn input_generator(input: &str) -> Result<Array2<u32>, nom::Err<()>> {
-    use nom::{
-        character::complete::{line_ending, satisfy},
-        multi::{many1, separated_list1},
-        Parser,
-    };
-
-    let digit = satisfy(|c| c.is_digit(10)).map(|c| c.to_digit(10).unwrap());
-    let line = many1(digit);
-    let mut parser = separated_list1(line_ending, line)
-        .map(|v| Array::from_shape_vec((10, 10), v.into_iter().flatten().collect()).unwrap());
-
-    parser.parse(input).map(|(_, p)| p)
+pub fn input_generator(input: &str) -> Result<Grid<u32>, ParseError> {
+    use crate::{grid::grid, parse::parse_all};
+
+    parse_all(11, input, grid)
 }
 
-fn perform_step(mut input: ArrayViewMut2<u32>) -> usize {
-    input += 1;
-    let mut has_flashed = Array2::from_elem((10, 10), false);
+fn perform_step(input: &mut Grid<u32>) -> usize {
+    input.iter_mut().for_each(|v| *v += 1);
+    let mut has_flashed = input.map(|_| false);
     loop {
         let pos: Vec<_> = input
             .indexed_iter()
@@ -37,17 +27,12 @@ fn perform_step(mut input: ArrayViewMut2<u32>) -> usize {
             break;
         }
 
-        for (x, y) in pos.into_iter() {
-            let x_min = x.checked_sub(1).unwrap_or(x);
-            let y_min = y.checked_sub(1).unwrap_or(y);
-
-            let x_max = (x + 1).min(9);
-            let y_max = (y + 1).min(9);
-
-            input
-                .slice_mut(s![x_min..=x_max, y_min..=y_max])
-                .add_assign(1);
-            has_flashed[(x, y)] = true;
+        for pos in pos.into_iter() {
+            let neighbors: Vec<_> = input.neighbors8(pos).collect();
+            for n in neighbors {
+                input[n] += 1;
+            }
+            has_flashed[pos] = true;
         }
     }
 
@@ -55,25 +40,48 @@ fn perform_step(mut input: ArrayViewMut2<u32>) -> usize {
 }
 
 #[aoc(day11, part1)]
-pub fn solve_part1(input: &Array2<u32>) -> usize {
+pub fn solve_part1(input: &Grid<u32>) -> usize {
     let mut input = input.clone();
-    (0..100).map(|_| perform_step(input.view_mut())).sum()
+    (0..100).map(|_| perform_step(&mut input)).sum()
 }
 
 #[aoc(day11, part2)]
-pub fn solve_part2(input: &Array2<u32>) -> usize {
+pub fn solve_part2(input: &Grid<u32>) -> usize {
     let mut input = input.clone();
+    let size = input.rows() * input.cols();
     let mut i = 0;
 
     loop {
-        let flashes = perform_step(input.view_mut());
+        let flashes = perform_step(&mut input);
         i += 1;
-        if flashes == 100 {
+        if flashes == size {
             break i;
         }
     }
 }
 
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+
+    type Input = Grid<u32>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        solve_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        solve_part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,216 @@
+use super::*;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaveKind {
+    Start,
+    End,
+    Small,
+    Big,
+}
+
+impl CaveKind {
+    fn of(name: &str) -> Self {
+        match name {
+            "start" => CaveKind::Start,
+            "end" => CaveKind::End,
+            name if name.chars().all(|c| c.is_ascii_lowercase()) => CaveKind::Small,
+            _ => CaveKind::Big,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CaveSystem {
+    names: Vec<String>,
+    kinds: Vec<CaveKind>,
+    edges: Vec<Vec<usize>>,
+    start: usize,
+    end: usize,
+}
+
+impl CaveSystem {
+    fn cave(&mut self, name: &str) -> usize {
+        match self.names.iter().position(|n| n == name) {
+            Some(id) => id,
+            None => {
+                self.names.push(name.to_owned());
+                self.kinds.push(CaveKind::of(name));
+                self.edges.push(Vec::new());
+                self.names.len() - 1
+            }
+        }
+    }
+
+    pub fn kind(&self, name: &str) -> Option<CaveKind> {
+        let id = self.names.iter().position(|n| n == name)?;
+        Some(self.kinds[id])
+    }
+
+    // number of paths from `cave` to the end, `visited` holds the small caves already seen and
+    // `twice` whether one of them may still be visited a second time
+    fn count_paths(
+        &self,
+        cave: usize,
+        visited: u64,
+        twice: bool,
+        memo: &mut HashMap<(usize, u64, bool), usize>,
+    ) -> usize {
+        if cave == self.end {
+            return 1;
+        }
+        if let Some(&paths) = memo.get(&(cave, visited, twice)) {
+            return paths;
+        }
+
+        let mut paths = 0;
+        for &next in &self.edges[cave] {
+            paths += match self.kinds[next] {
+                CaveKind::Start => 0,
+                CaveKind::Big => self.count_paths(next, visited, twice, memo),
+                CaveKind::Small | CaveKind::End if visited & 1 << next == 0 => {
+                    self.count_paths(next, visited | 1 << next, twice, memo)
+                }
+                CaveKind::Small if twice => self.count_paths(next, visited, false, memo),
+                _ => 0,
+            };
+        }
+
+        memo.insert((cave, visited, twice), paths);
+        paths
+    }
+
+    pub fn paths(&self, twice: bool) -> usize {
+        self.count_paths(self.start, 0, twice, &mut HashMap::new())
+    }
+}
+
+#[aoc_generator(day12)]
+pub fn input_generator(input: &str) -> Result<CaveSystem, ParseError> {
+    use crate::parse::{lines, parse_all};
+    use nom::{
+        character::complete::{alpha1, char},
+        combinator::{consumed, verify},
+        error::context,
+        sequence::separated_pair,
+    };
+
+    let name = || {
+        context(
+            "a cave name",
+            verify(alpha1, |name: &str| {
+                name.chars().all(|c| c.is_ascii_lowercase())
+                    || name.chars().all(|c| c.is_ascii_uppercase())
+            }),
+        )
+    };
+    let edge = consumed(separated_pair(name(), char('-'), name()));
+    let edges = parse_all(12, input, lines(edge))?;
+
+    let mut caves = CaveSystem {
+        names: Vec::new(),
+        kinds: Vec::new(),
+        edges: Vec::new(),
+        start: 0,
+        end: 0,
+    };
+    for (line, (a, b)) in edges {
+        let (a, b) = (caves.cave(a), caves.cave(b));
+        if caves.names.len() > 64 {
+            return Err(ParseError::new(12, input, line, "at most 64 caves"));
+        }
+        // two connected big caves would allow infinitely many paths
+        if caves.kinds[a] == CaveKind::Big && caves.kinds[b] == CaveKind::Big {
+            return Err(ParseError::new(
+                12,
+                input,
+                line,
+                "no passage between two big caves",
+            ));
+        }
+        caves.edges[a].push(b);
+        caves.edges[b].push(a);
+    }
+
+    let find = |name, expected| {
+        let end_of_input = &input[input.len()..];
+        caves
+            .names
+            .iter()
+            .position(|n| n == name)
+            .ok_or_else(|| ParseError::new(12, input, end_of_input, expected))
+    };
+    let start = find("start", "a passage from the `start` cave")?;
+    let end = find("end", "a passage to the `end` cave")?;
+    caves.start = start;
+    caves.end = end;
+
+    Ok(caves)
+}
+
+#[aoc(day12, part1)]
+pub fn solve_part1(caves: &CaveSystem) -> usize {
+    caves.paths(false)
+}
+
+#[aoc(day12, part2)]
+pub fn solve_part2(caves: &CaveSystem) -> usize {
+    caves.paths(true)
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: u8 = 12;
+
+    type Input = CaveSystem;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        solve_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        solve_part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static SMALL: &str = "start-A\nstart-b\nA-c\nA-b\nb-d\nA-end\nb-end";
+    static MEDIUM: &str =
+        "dc-end\nHN-start\nstart-kj\ndc-start\ndc-HN\nLN-dc\nHN-end\nkj-sa\nkj-HN\nkj-dc";
+    static LARGE: &str = "fs-end\nhe-DX\nfs-he\nstart-DX\npj-DX\nend-zg\nzg-sl\nzg-pj\npj-he\nRW-he\nfs-DX\npj-RW\nzg-RW\nstart-pj\nhe-WI\nzg-he\npj-fs\nstart-RW";
+
+    #[test]
+    fn test_parse() {
+        let caves = input_generator(SMALL).unwrap();
+        assert_eq!(caves.kind("start"), Some(CaveKind::Start));
+        assert_eq!(caves.kind("A"), Some(CaveKind::Big));
+        assert_eq!(caves.kind("b"), Some(CaveKind::Small));
+
+        let err = input_generator("start-A\nA-B\nA-end").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(solve_part1(&input_generator(SMALL).unwrap()), 10);
+        assert_eq!(solve_part1(&input_generator(MEDIUM).unwrap()), 19);
+        assert_eq!(solve_part1(&input_generator(LARGE).unwrap()), 226);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(solve_part2(&input_generator(SMALL).unwrap()), 36);
+        assert_eq!(solve_part2(&input_generator(MEDIUM).unwrap()), 103);
+        assert_eq!(solve_part2(&input_generator(LARGE).unwrap()), 3509);
+    }
+}
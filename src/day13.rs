@@ -1,4 +1,5 @@
 use super::*;
+use crate::grid::Grid;
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 pub struct Point {
@@ -19,44 +20,44 @@ pub enum FoldInstructionKind {
 }
 
 #[aoc_generator(day13)]
-pub fn input_generator(input: &str) -> Result<(Vec<Point>, Vec<FoldInstruction>), nom::Err<()>> {
+pub fn input_generator(input: &str) -> Result<(Vec<Point>, Vec<FoldInstruction>), ParseError> {
+    use crate::parse::{coord, lines, parse_all, sections};
     use nom::{
         bytes::complete::tag,
-        character::complete::{char, line_ending, u32},
+        character::complete::{char, u32},
         combinator::value,
-        multi::separated_list1,
+        error::context,
         sequence::separated_pair,
         Parser,
     };
 
-    let point = separated_pair(u32, char(','), u32).map(|(x, y)| Point {
+    let point = coord.map(|(x, y)| Point {
         x: x as usize,
         y: y as usize,
     });
-    let points = separated_list1(line_ending, point);
+    let points = lines(point);
 
     let instruction = separated_pair(
-        tag("fold along ").and(
+        context("`fold along`", tag("fold along ")).and(context(
+            "`x` or `y`",
             value(FoldInstructionKind::Left, char('x'))
                 .or(value(FoldInstructionKind::Up, char('y'))),
-        ),
+        )),
         char('='),
-        u32,
+        context("a position", u32),
     )
     .map(|((_, kind), position)| FoldInstruction {
         kind,
         position: position as usize,
     });
-    let instructions = separated_list1(line_ending, instruction);
+    let instructions = lines(instruction);
 
-    let mut parser = separated_pair(points, line_ending.and(line_ending), instructions);
-
-    parser.parse(input).map(|(_, i)| i)
+    parse_all(13, input, sections(points, instructions))
 }
 
 #[aoc(day13, part1)]
 pub fn solve_part1((points, instructions): &(Vec<Point>, Vec<FoldInstruction>)) -> usize {
-    let instruction = instructions.get(0).unwrap();
+    let instruction = instructions.first().unwrap();
 
     let mut points: Vec<_> = points
         .iter()
@@ -82,9 +83,7 @@ pub fn solve_part1((points, instructions): &(Vec<Point>, Vec<FoldInstruction>))
 }
 
 #[aoc(day13, part2)]
-pub fn solve_part2(
-    (points, instructions): &(Vec<Point>, Vec<FoldInstruction>),
-) -> ndarray::Array2<usize> {
+pub fn solve_part2((points, instructions): &(Vec<Point>, Vec<FoldInstruction>)) -> Grid<bool> {
     let mut points = points.clone();
     for instruction in instructions {
         points = points
@@ -110,21 +109,38 @@ pub fn solve_part2(
     let i = points.iter().map(|p| p.x).max().unwrap();
     let j = points.iter().map(|p| p.y).max().unwrap();
 
-    points.into_iter().fold(
-        ndarray::Array2::from_elem((j + 1, i + 1), 0),
-        |mut arr, p| {
-            arr[(p.y, p.x)] = 1;
-            arr
-        },
-    )
+    points
+        .into_iter()
+        .fold(Grid::from_elem(j + 1, i + 1, false), |mut grid, p| {
+            grid[(p.y, p.x)] = true;
+            grid
+        })
 }
 
-#[cfg(test)]
-mod tests {
-    use std::ops::AddAssign;
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: u8 = 13;
+
+    type Input = (Vec<Point>, Vec<FoldInstruction>);
+    type Output1 = usize;
+    type Output2 = Grid<bool>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        solve_part1(input)
+    }
 
-    use ndarray::{s, Array2};
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        solve_part2(input)
+    }
+}
 
+#[cfg(test)]
+mod tests {
     use super::*;
 
     static INPUT: &str = "6,10\n0,14\n9,10\n0,3\n10,4\n4,11\n6,0\n6,12\n4,1\n0,13\n10,12\n3,4\n3,0\n8,4\n1,10\n2,14\n8,10\n9,0\n\nfold along y=7\nfold along x=5";
@@ -136,12 +152,9 @@ mod tests {
 
     #[test]
     fn test_part2() {
-        let mut arr = Array2::from_elem((5, 5), 0);
-        arr.slice_mut(s![.., 0]).add_assign(1);
-        arr.slice_mut(s![.., -1]).add_assign(1);
-        arr.slice_mut(s![0, 1..-1]).add_assign(1);
-        arr.slice_mut(s![-1, 1..-1]).add_assign(1);
-
-        assert_eq!(solve_part2(&input_generator(INPUT).unwrap()), arr)
+        assert_eq!(
+            solve_part2(&input_generator(INPUT).unwrap()).to_string(),
+            "#####\n#...#\n#...#\n#...#\n#####"
+        )
     }
 }
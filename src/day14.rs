@@ -1,25 +1,33 @@
 use super::*;
+use crate::solution::Maybe;
 use itertools::Itertools;
 use std::collections::HashMap;
 
+pub type Input = (String, HashMap<(char, char), char>);
+
 #[aoc_generator(day14)]
-pub fn input_generator(input: &str) -> Result<(String, HashMap<(char, char), char>), nom::Err<()>> {
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    use crate::parse::{lines, parse_all, sections};
     use nom::{
         bytes::complete::tag,
-        character::complete::{alpha1, anychar, line_ending},
-        multi::separated_list1,
+        character::complete::{alpha1, satisfy},
+        error::context,
         sequence::separated_pair,
         Parser,
     };
 
-    let pair_insertion = separated_pair(anychar.and(anychar), tag(" -> "), anychar);
-    let pair_insertions = separated_list1(line_ending, pair_insertion);
+    let element = || context("an element", satisfy(|c| c.is_ascii_uppercase()));
+    let pair_insertion = separated_pair(
+        element().and(element()),
+        context("` -> `", tag(" -> ")),
+        element(),
+    );
+    let pair_insertions = lines(pair_insertion);
 
-    let mut parser = separated_pair(alpha1, line_ending.and(line_ending), pair_insertions);
+    let parser = sections(context("a polymer template", alpha1), pair_insertions)
+        .map(|(s, i): (&str, Vec<_>)| (s.to_owned(), i.into_iter().collect()));
 
-    parser
-        .parse(input)
-        .map(|(_, (s, i))| (s.to_owned(), i.into_iter().collect()))
+    parse_all(14, input, parser)
 }
 
 fn calculate_step(
@@ -60,19 +68,37 @@ fn calculate_step(
 }
 
 #[aoc(day14, part1)]
-pub fn solve_part1(
-    (template, instructions): &(String, HashMap<(char, char), char>),
-) -> Option<u64> {
+pub fn solve_part1((template, instructions): &Input) -> Option<u64> {
     calculate_step(template, instructions, 10)
 }
 
 #[aoc(day14, part2)]
-pub fn solve_part2(
-    (template, instructions): &(String, HashMap<(char, char), char>),
-) -> Option<u64> {
+pub fn solve_part2((template, instructions): &Input) -> Option<u64> {
     calculate_step(template, instructions, 40)
 }
 
+pub struct Day14;
+
+impl Solution for Day14 {
+    const DAY: u8 = 14;
+
+    type Input = Input;
+    type Output1 = Maybe<u64>;
+    type Output2 = Maybe<u64>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        Maybe(solve_part1(input))
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        Maybe(solve_part2(input))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -91,4 +117,11 @@ mod tests {
             Some(2188189693529)
         );
     }
+
+    #[test]
+    fn test_single_element() {
+        // a template without pairs has nothing to insert into
+        let input = input_generator("N\n\nNN -> C").unwrap();
+        assert_eq!(solve_part1(&input), None);
+    }
 }
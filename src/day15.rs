@@ -0,0 +1,157 @@
+use super::*;
+use crate::grid::Grid;
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+#[aoc_generator(day15)]
+pub fn input_generator(input: &str) -> Result<Grid<u32>, ParseError> {
+    use crate::{grid::grid, parse::parse_all};
+
+    let map: Grid<u32> = parse_all(15, input, grid)?;
+
+    if let Some(((r, c), _)) = map.indexed_iter().find(|(_, &risk)| risk == 0) {
+        let line = input.lines().nth(r).unwrap();
+        return Err(ParseError::new(
+            15,
+            input,
+            &line[c..],
+            "a risk level from 1 to 9",
+        ));
+    }
+
+    Ok(map)
+}
+
+/// The risk map repeated `tiles` times in both directions, risks are computed on access.
+pub struct TiledMap<'a> {
+    grid: &'a Grid<u32>,
+    tiles: usize,
+}
+
+impl<'a> TiledMap<'a> {
+    pub fn new(grid: &'a Grid<u32>, tiles: usize) -> Self {
+        Self { grid, tiles }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.grid.rows() * self.tiles
+    }
+
+    pub fn cols(&self) -> usize {
+        self.grid.cols() * self.tiles
+    }
+
+    pub fn risk(&self, (r, c): (usize, usize)) -> u32 {
+        let (rows, cols) = (self.grid.rows(), self.grid.cols());
+        let increment = (r / rows + c / cols) as u32;
+
+        // risks above 9 wrap around to 1
+        (self.grid[(r % rows, c % cols)] + increment - 1) % 9 + 1
+    }
+
+    fn neighbors(&self, (r, c): (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+        let (rows, cols) = (self.rows(), self.cols());
+        [
+            r.checked_sub(1).map(|r| (r, c)),
+            c.checked_sub(1).map(|c| (r, c)),
+            (c + 1 < cols).then_some((r, c + 1)),
+            (r + 1 < rows).then_some((r + 1, c)),
+        ]
+        .into_iter()
+        .flatten()
+    }
+
+    /// Total risk of the safest path from the top left to the bottom right corner.
+    pub fn lowest_risk(&self) -> Option<u32> {
+        let cols = self.cols();
+        let target = (self.rows() - 1, cols - 1);
+        let idx = |(r, c): (usize, usize)| r * cols + c;
+
+        let mut risks = vec![u32::MAX; self.rows() * cols];
+        let mut queue = BinaryHeap::new();
+        risks[0] = 0;
+        queue.push(Reverse((0, (0, 0))));
+
+        while let Some(Reverse((risk, pos))) = queue.pop() {
+            if pos == target {
+                return Some(risk);
+            }
+            if risk > risks[idx(pos)] {
+                continue;
+            }
+
+            for next in self.neighbors(pos) {
+                let next_risk = risk + self.risk(next);
+                if next_risk < risks[idx(next)] {
+                    risks[idx(next)] = next_risk;
+                    queue.push(Reverse((next_risk, next)));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+#[aoc(day15, part1)]
+pub fn solve_part1(grid: &Grid<u32>) -> Option<u32> {
+    TiledMap::new(grid, 1).lowest_risk()
+}
+
+#[aoc(day15, part2)]
+pub fn solve_part2(grid: &Grid<u32>) -> Option<u32> {
+    TiledMap::new(grid, 5).lowest_risk()
+}
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    const DAY: u8 = 15;
+
+    type Input = Grid<u32>;
+    type Output1 = u32;
+    type Output2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        solve_part1(input).expect("every position of the grid is reachable")
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        solve_part2(input).expect("every position of the grid is reachable")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static INPUT: &str = "1163751742\n1381373672\n2136511328\n3694931569\n7463417111\n1319128137\n1359912421\n3125421639\n1293138521\n2311944581";
+
+    #[test]
+    fn test_invalid_risk() {
+        let err = input_generator("19\n90").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+    }
+
+    #[test]
+    fn test_tiled_risk() {
+        let grid = input_generator("8").unwrap();
+        let map = TiledMap::new(&grid, 5);
+        let risks: Vec<_> = (0..5).map(|c| map.risk((0, c))).collect();
+        assert_eq!(risks, [8, 9, 1, 2, 3]);
+        assert_eq!(map.risk((4, 4)), 7);
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(solve_part1(&input_generator(INPUT).unwrap()), Some(40))
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(solve_part2(&input_generator(INPUT).unwrap()), Some(315))
+    }
+}
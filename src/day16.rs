@@ -92,105 +92,159 @@ impl Packet {
     }
 }
 
-fn get_bytes(input: &str) -> Result<Vec<u8>, nom::Err<()>> {
-    use nom::{bytes::complete::take, multi::many1, Parser};
+// a packet that could not be parsed, `at` is the remaining input where it broke
+#[derive(Debug)]
+struct PacketError<'a> {
+    at: &'a BitSlice<Msb0, u8>,
+    expected: &'static str,
+}
 
-    let mut parser = many1(take(2usize).map(|v| u8::from_str_radix(v, 16).unwrap()));
-    let (_, bytes) = parser.parse(input)?;
+type PacketResult<'a, T> = Result<(T, &'a BitSlice<Msb0, u8>), PacketError<'a>>;
+
+fn take_bits<'a>(
+    input: &'a BitSlice<Msb0, u8>,
+    n: usize,
+    expected: &'static str,
+) -> PacketResult<'a, &'a BitSlice<Msb0, u8>> {
+    if input.len() < n {
+        return Err(PacketError {
+            at: input,
+            expected,
+        });
+    }
 
-    Ok(bytes)
+    Ok(input.split_at(n))
+}
+
+fn get_bytes(input: &str) -> Result<Vec<u8>, ParseError> {
+    use crate::parse::parse_all;
+    use nom::{bytes::complete::take_while_m_n, combinator::map_res, error::context, multi::many1};
+
+    let byte = map_res(take_while_m_n(2, 2, |c: char| c.is_ascii_hexdigit()), |v| {
+        u8::from_str_radix(v, 16)
+    });
+
+    parse_all(16, input, many1(context("two hexadecimal digits", byte)))
 }
 
 #[aoc_generator(day16)]
-pub fn input_generator(input: &str) -> Result<Packet, nom::Err<()>> {
+pub fn input_generator(input: &str) -> Result<Packet, ParseError> {
+    let input = input.trim_end();
     let bytes = get_bytes(input)?;
     let bits = bytes.view_bits::<Msb0>();
-    let (packet, _) = parse_packet(bits);
+    let (packet, _) = parse_packet(bits).map_err(|e| {
+        // every hex digit holds four bits
+        let offset = (bits.len() - e.at.len()) / 4;
+        ParseError::new(16, input, &input[offset.min(input.len())..], e.expected)
+    })?;
     Ok(packet)
 }
 
-fn parse_header(input: &BitSlice<Msb0, u8>) -> (u8, u8, &BitSlice<Msb0, u8>) {
-    let (version, input) = input.split_at(3);
-    let (id, input) = input.split_at(3);
+fn parse_header(input: &BitSlice<Msb0, u8>) -> PacketResult<'_, (u8, u8)> {
+    let (version, input) = take_bits(input, 3, "a packet version")?;
+    let (id, input) = take_bits(input, 3, "a packet type")?;
 
-    (version.load_be(), id.load_be(), input)
+    Ok(((version.load_be(), id.load_be()), input))
 }
 
-fn parse_literal(input: &BitSlice<Msb0, u8>) -> (u64, &BitSlice<Msb0, u8>) {
-    let n = input
-        .iter()
-        .by_ref()
-        .step_by(5)
-        .enumerate()
-        .skip_while(|(_, i)| **i)
-        .map(|(i, _)| i + 1)
-        .next()
-        .unwrap();
-    let (contents, input) = input.split_at(5 * n);
-    let literal = BitVec::<Msb0, u8>::from_iter(
-        contents
-            .iter()
-            .enumerate()
-            .filter(|(i, _)| i % 5 != 0)
-            .map(|(_, b)| b),
-    );
-
-    (literal.load_be(), input)
+fn parse_literal(mut input: &BitSlice<Msb0, u8>) -> PacketResult<'_, u64> {
+    let mut literal = 0u64;
+
+    // a u64 holds at most 16 groups of four bits
+    for _ in 0..16 {
+        let (group, rest) = take_bits(input, 5, "a literal group")?;
+        literal = literal << 4 | group[1..].load_be::<u64>();
+        input = rest;
+
+        if !group[0] {
+            return Ok((literal, input));
+        }
+    }
+
+    Err(PacketError {
+        at: input,
+        expected: "a literal of at most 64 bits",
+    })
 }
 
-fn parse_operator(input: &BitSlice<Msb0, u8>) -> (Vec<Packet>, &BitSlice<Msb0, u8>) {
-    let (len_id, input) = input.split_first().unwrap();
+fn parse_operator(input: &BitSlice<Msb0, u8>) -> PacketResult<'_, Vec<Packet>> {
+    let (len_id, input) = take_bits(input, 1, "a length type")?;
+    let mut packets = vec![];
 
-    if *len_id {
-        let (num_packets, input) = input.split_at(11);
-        let num_packets = num_packets.load_be::<u16>();
-        let (packets, input) = (0..num_packets).fold((vec![], input), |(mut out, input), _| {
-            let (packet, input) = parse_packet(input);
-            out.push(packet);
-            (out, input)
-        });
-        (packets, input)
-    } else {
-        let (num_bits, input) = input.split_at(15);
-        let num_bits = num_bits.load_be();
-        let (mut bits, input) = input.split_at(num_bits);
-
-        let mut packets = vec![];
-        let packets = loop {
-            let (packet, b) = parse_packet(bits);
-            bits = b;
+    if len_id[0] {
+        let (num_packets, mut input) = take_bits(input, 11, "a number of sub-packets")?;
+        for _ in 0..num_packets.load_be::<u16>() {
+            let (packet, rest) = parse_packet(input)?;
             packets.push(packet);
-            if bits.is_empty() {
-                break packets;
+            input = rest;
+        }
+        Ok((packets, input))
+    } else {
+        let (num_bits, mut input) = take_bits(input, 15, "a length of sub-packets")?;
+        // sub-packets are parsed in place, the remaining length tells when they are done
+        let end = input
+            .len()
+            .checked_sub(num_bits.load_be::<usize>())
+            .ok_or(PacketError {
+                at: input,
+                expected: "as many bits as the sub-packet length",
+            })?;
+
+        while input.len() > end {
+            let (packet, rest) = parse_packet(input)?;
+            if rest.len() < end {
+                return Err(PacketError {
+                    at: input,
+                    expected: "sub-packets that fit their length",
+                });
             }
-        };
-
-        (packets, input)
+            packets.push(packet);
+            input = rest;
+        }
+        Ok((packets, input))
     }
 }
 
-fn parse_packet(input: &BitSlice<Msb0, u8>) -> (Packet, &BitSlice<Msb0, u8>) {
-    let (version, id, input) = parse_header(input);
+fn parse_packet(input: &BitSlice<Msb0, u8>) -> PacketResult<'_, Packet> {
+    let ((version, id), rest) = parse_header(input)?;
 
     match id {
         4 => {
-            let (literal, input) = parse_literal(input);
+            let (literal, rest) = parse_literal(rest)?;
             let packet = Packet {
                 version,
                 type_id: TypeId::Literal(literal),
             };
-            (packet, input)
+            Ok((packet, rest))
         }
         id => {
-            let (packets, input) = parse_operator(input);
+            let (packets, rest) = parse_operator(rest)?;
+            let kind = id.try_into().unwrap();
+
+            let expected = match kind {
+                Operation::GreaterThan | Operation::LessThan | Operation::EqualTo
+                    if packets.len() != 2 =>
+                {
+                    Some("exactly two sub-packets")
+                }
+                _ if packets.is_empty() => Some("at least one sub-packet"),
+                _ => None,
+            };
+            if let Some(expected) = expected {
+                return Err(PacketError {
+                    at: input,
+                    expected,
+                });
+            }
+
             let packet = Packet {
                 version,
                 type_id: TypeId::Operator {
-                    kind: id.try_into().unwrap(),
+                    kind,
                     content: packets,
                 },
             };
-            (packet, input)
+            Ok((packet, rest))
         }
     }
 }
@@ -205,6 +259,28 @@ pub fn solve_part2(packet: &Packet) -> u64 {
     packet.get_value()
 }
 
+pub struct Day16;
+
+impl Solution for Day16 {
+    const DAY: u8 = 16;
+
+    type Input = Packet;
+    type Output1 = u32;
+    type Output2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        solve_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        solve_part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -212,7 +288,7 @@ mod tests {
     #[test]
     fn test_parse_literal_packet() {
         let bytes = get_bytes("D2FE28").unwrap();
-        let (packet, _) = parse_packet(bytes.view_bits());
+        let (packet, _) = parse_packet(bytes.view_bits()).unwrap();
         assert_eq!(
             packet,
             Packet {
@@ -225,7 +301,7 @@ mod tests {
     #[test]
     fn test_parse_operator_packet_type0() {
         let bytes = get_bytes("38006F45291200").unwrap();
-        let (packet, _) = parse_packet(bytes.view_bits());
+        let (packet, _) = parse_packet(bytes.view_bits()).unwrap();
         assert_eq!(
             packet,
             Packet {
@@ -250,7 +326,7 @@ mod tests {
     #[test]
     fn test_parse_operator_packet_type1() {
         let bytes = get_bytes("EE00D40C823060").unwrap();
-        let (packet, _) = parse_packet(bytes.view_bits());
+        let (packet, _) = parse_packet(bytes.view_bits()).unwrap();
         assert_eq!(
             packet,
             Packet {
@@ -279,26 +355,34 @@ mod tests {
     #[test]
     fn test_parse_packet() {
         let bytes = get_bytes("8A004A801A8002F478").unwrap();
-        let (packet, _) = parse_packet(bytes.view_bits());
+        let (packet, _) = parse_packet(bytes.view_bits()).unwrap();
         assert_eq!(packet.sum_versions(), 16);
 
         let bytes = get_bytes("620080001611562C8802118E34").unwrap();
-        let (packet, _) = parse_packet(bytes.view_bits());
+        let (packet, _) = parse_packet(bytes.view_bits()).unwrap();
         assert_eq!(packet.sum_versions(), 12);
 
         let bytes = get_bytes("C0015000016115A2E0802F182340").unwrap();
-        let (packet, _) = parse_packet(bytes.view_bits());
+        let (packet, _) = parse_packet(bytes.view_bits()).unwrap();
         assert_eq!(packet.sum_versions(), 23);
 
         let bytes = get_bytes("A0016C880162017C3686B18A3D4780").unwrap();
-        let (packet, _) = parse_packet(bytes.view_bits());
+        let (packet, _) = parse_packet(bytes.view_bits()).unwrap();
         assert_eq!(packet.sum_versions(), 31)
     }
 
+    #[test]
+    fn test_truncated_packet() {
+        let err = input_generator("D2FE").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 5));
+        assert_eq!(err.expected, "a literal group");
+        assert_eq!(err.found, "end of input");
+    }
+
     #[test]
     fn test_sum_packet() {
         let bytes = get_bytes("C200B40A82").unwrap();
-        let (packet, _) = parse_packet(bytes.view_bits());
+        let (packet, _) = parse_packet(bytes.view_bits()).unwrap();
 
         assert_eq!(packet.get_value(), 3)
     }
@@ -306,7 +390,7 @@ mod tests {
     #[test]
     fn test_product_packet() {
         let bytes = get_bytes("04005AC33890").unwrap();
-        let (packet, _) = parse_packet(bytes.view_bits());
+        let (packet, _) = parse_packet(bytes.view_bits()).unwrap();
 
         assert_eq!(packet.get_value(), 54)
     }
@@ -314,7 +398,7 @@ mod tests {
     #[test]
     fn test_minimum_packet() {
         let bytes = get_bytes("880086C3E88112").unwrap();
-        let (packet, _) = parse_packet(bytes.view_bits());
+        let (packet, _) = parse_packet(bytes.view_bits()).unwrap();
 
         assert_eq!(packet.get_value(), 7)
     }
@@ -322,7 +406,7 @@ mod tests {
     #[test]
     fn test_maximum_packet() {
         let bytes = get_bytes("CE00C43D881120").unwrap();
-        let (packet, _) = parse_packet(bytes.view_bits());
+        let (packet, _) = parse_packet(bytes.view_bits()).unwrap();
 
         assert_eq!(packet.get_value(), 9)
     }
@@ -330,7 +414,7 @@ mod tests {
     #[test]
     fn test_less_than_packet() {
         let bytes = get_bytes("D8005AC2A8F0").unwrap();
-        let (packet, _) = parse_packet(bytes.view_bits());
+        let (packet, _) = parse_packet(bytes.view_bits()).unwrap();
 
         assert_eq!(packet.get_value(), 1)
     }
@@ -338,7 +422,7 @@ mod tests {
     #[test]
     fn test_greater_than_packet() {
         let bytes = get_bytes("F600BC2D8F").unwrap();
-        let (packet, _) = parse_packet(bytes.view_bits());
+        let (packet, _) = parse_packet(bytes.view_bits()).unwrap();
 
         assert_eq!(packet.get_value(), 0)
     }
@@ -346,7 +430,7 @@ mod tests {
     #[test]
     fn test_equals_to_packet() {
         let bytes = get_bytes("9C005AC2F8F0").unwrap();
-        let (packet, _) = parse_packet(bytes.view_bits());
+        let (packet, _) = parse_packet(bytes.view_bits()).unwrap();
 
         assert_eq!(packet.get_value(), 0)
     }
@@ -354,7 +438,7 @@ mod tests {
     #[test]
     fn test_complex_packet() {
         let bytes = get_bytes("9C0141080250320F1802104A08").unwrap();
-        let (packet, _) = parse_packet(bytes.view_bits());
+        let (packet, _) = parse_packet(bytes.view_bits()).unwrap();
 
         assert_eq!(packet.get_value(), 1)
     }
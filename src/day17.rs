@@ -0,0 +1,201 @@
+use super::*;
+use crate::solution::Maybe;
+use std::ops::RangeInclusive;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Target {
+    x: RangeInclusive<i32>,
+    y: RangeInclusive<i32>,
+}
+
+// keeps the velocity bounds and the simulated positions well inside of `i32`
+const MAX_COORDINATE: i32 = 10_000;
+
+#[aoc_generator(day17)]
+pub fn input_generator(input: &str) -> Result<Target, ParseError> {
+    use crate::parse::parse_all;
+    use nom::{
+        bytes::complete::tag,
+        character::complete::i32,
+        combinator::verify,
+        error::context,
+        sequence::{preceded, separated_pair},
+        Parser,
+    };
+
+    let range = || {
+        verify(
+            separated_pair(i32, tag(".."), i32),
+            |(from, to): &(i32, i32)| {
+                -MAX_COORDINATE <= *from && from <= to && *to <= MAX_COORDINATE
+            },
+        )
+    };
+    let x = context("a range of x coordinates", range());
+    // a target at the height of the launcher could be hit with arbitrarily high shots
+    let y = context(
+        "a range of y coordinates above or below the launcher",
+        verify(range(), |(from, to): &(i32, i32)| *to < 0 || *from > 0),
+    );
+
+    let parser = preceded(
+        context("`target area: x=`", tag("target area: x=")),
+        separated_pair(x, context("`, y=`", tag(", y=")), y),
+    )
+    .map(|((x1, x2), (y1, y2))| Target {
+        x: x1..=x2,
+        y: y1..=y2,
+    });
+
+    parse_all(17, input, parser)
+}
+
+// smallest speed that covers a distance of `d` before drag stops it
+fn min_speed(d: i32) -> i32 {
+    let mut v = ((2.0 * d as f64).sqrt() as i32 - 1).max(0);
+    while v * (v + 1) / 2 < d {
+        v += 1;
+    }
+    v
+}
+
+impl Target {
+    /// Initial velocities outside of these ranges can never end up inside of the target.
+    pub fn velocity_bounds(&self) -> (RangeInclusive<i32>, RangeInclusive<i32>) {
+        let (&x1, &x2) = (self.x.start(), self.x.end());
+        let (&y1, &y2) = (self.y.start(), self.y.end());
+
+        // too slow and drag stops the probe short, too fast and it skips past on the first step
+        let vx = if x1 > 0 {
+            min_speed(x1)..=x2
+        } else if x2 < 0 {
+            x1..=-min_speed(-x2)
+        } else {
+            x1..=x2
+        };
+
+        // a probe shot upwards comes back to height 0 with its initial speed, the next step must
+        // not skip the target
+        let vy = if y2 < 0 {
+            y1..=-y1 - 1
+        } else {
+            min_speed(y1)..=y2
+        };
+
+        (vx, vy)
+    }
+
+    pub fn hits(&self, (mut vx, mut vy): (i32, i32)) -> bool {
+        let (mut x, mut y) = (0, 0);
+
+        loop {
+            x += vx;
+            y += vy;
+            vx -= vx.signum();
+            vy -= 1;
+
+            if self.x.contains(&x) && self.y.contains(&y) {
+                return true;
+            }
+
+            let falling_past = vy < 0 && y < *self.y.start();
+            let passed_x = (vx >= 0 && x > *self.x.end()) || (vx <= 0 && x < *self.x.start());
+            if falling_past || passed_x {
+                return false;
+            }
+        }
+    }
+
+    /// All initial velocities that put the probe inside of the target after some step.
+    pub fn velocities(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        let (vx, vy) = self.velocity_bounds();
+        vx.flat_map(move |x| vy.clone().map(move |y| (x, y)))
+            .filter(|&v| self.hits(v))
+    }
+}
+
+fn apex(vy: i32) -> i32 {
+    if vy > 0 {
+        vy * (vy + 1) / 2
+    } else {
+        0
+    }
+}
+
+#[aoc(day17, part1)]
+pub fn solve_part1(target: &Target) -> Option<i32> {
+    target.velocities().map(|(_, vy)| apex(vy)).max()
+}
+
+#[aoc(day17, part2)]
+pub fn solve_part2(target: &Target) -> usize {
+    target.velocities().count()
+}
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    const DAY: u8 = 17;
+
+    type Input = Target;
+    type Output1 = Maybe<i32>;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        Maybe(solve_part1(input))
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        solve_part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static INPUT: &str = "target area: x=20..30, y=-10..-5";
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(solve_part1(&input_generator(INPUT).unwrap()), Some(45))
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(solve_part2(&input_generator(INPUT).unwrap()), 112)
+    }
+
+    #[test]
+    fn test_mirrored_targets() {
+        let left = input_generator("target area: x=-30..-20, y=-10..-5").unwrap();
+        assert_eq!(solve_part1(&left), Some(45));
+        assert_eq!(solve_part2(&left), 112);
+
+        let above = input_generator("target area: x=20..30, y=5..10").unwrap();
+        assert_eq!(solve_part1(&above), Some(55));
+        assert!(above.velocities().all(|(_, vy)| vy > 0));
+
+        assert!(input_generator("target area: x=20..30, y=-5..5").is_err());
+    }
+
+    #[test]
+    fn test_far_targets() {
+        let far = input_generator("target area: x=9000..10000, y=-10000..-9000").unwrap();
+        assert_eq!(far.velocity_bounds(), (134..=10000, -10000..=9999));
+        assert!(far.hits((134, 9999)));
+        assert_eq!(apex(9999), 9999 * 10000 / 2);
+
+        let err = input_generator("target area: x=2000000000..2000000001, y=-10..-5").unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.expected.as_str()),
+            (1, 16, "a range of x coordinates")
+        );
+        let err = input_generator("target area: x=20..30, y=-2147483648..-5").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 26));
+    }
+}
@@ -0,0 +1,388 @@
+use super::*;
+use crate::{parse::IResult, solution::Maybe};
+use std::{
+    fmt::{self, Display},
+    iter::Sum,
+    ops::Add,
+    str::FromStr,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Number {
+    Regular(u32),
+    Pair(Box<Number>, Box<Number>),
+}
+
+use Number::{Pair, Regular};
+
+impl Number {
+    fn pair(left: Number, right: Number) -> Self {
+        Pair(Box::new(left), Box::new(right))
+    }
+
+    fn leftmost(&mut self) -> &mut u32 {
+        match self {
+            Regular(v) => v,
+            Pair(left, _) => left.leftmost(),
+        }
+    }
+
+    fn rightmost(&mut self) -> &mut u32 {
+        match self {
+            Regular(v) => v,
+            Pair(_, right) => right.rightmost(),
+        }
+    }
+
+    // explodes the leftmost pair of regular numbers nested inside of four pairs, returns the
+    // values that still have to be added to its left and right neighbors
+    fn explode_at(&mut self, depth: usize) -> Option<(Option<u32>, Option<u32>)> {
+        let Pair(left, right) = self else {
+            return None;
+        };
+
+        if depth >= 4 {
+            if let (Regular(a), Regular(b)) = (&**left, &**right) {
+                let carry = (Some(*a), Some(*b));
+                *self = Regular(0);
+                return Some(carry);
+            }
+        }
+
+        if let Some((carry_left, carry_right)) = left.explode_at(depth + 1) {
+            if let Some(v) = carry_right {
+                *right.leftmost() += v;
+            }
+            return Some((carry_left, None));
+        }
+        if let Some((carry_left, carry_right)) = right.explode_at(depth + 1) {
+            if let Some(v) = carry_left {
+                *left.rightmost() += v;
+            }
+            return Some((None, carry_right));
+        }
+
+        None
+    }
+
+    fn explode(&mut self) -> bool {
+        self.explode_at(0).is_some()
+    }
+
+    // splits the leftmost regular number of 10 or more
+    fn split(&mut self) -> bool {
+        match self {
+            Regular(v) if *v >= 10 => {
+                *self = Number::pair(Regular(*v / 2), Regular(v.div_ceil(2)));
+                true
+            }
+            Regular(_) => false,
+            Pair(left, right) => left.split() || right.split(),
+        }
+    }
+
+    // a single reduction step, explosions always take precedence over splits
+    fn reduce_step(&mut self) -> bool {
+        self.explode() || self.split()
+    }
+
+    fn reduce(&mut self) {
+        while self.reduce_step() {}
+    }
+
+    pub fn magnitude(&self) -> u64 {
+        match self {
+            Regular(v) => *v as u64,
+            Pair(left, right) => 3 * left.magnitude() + 2 * right.magnitude(),
+        }
+    }
+}
+
+impl Add for Number {
+    type Output = Number;
+
+    fn add(self, rhs: Number) -> Number {
+        let mut sum = Number::pair(self, rhs);
+        sum.reduce();
+        sum
+    }
+}
+
+/// Adds up all numbers from left to right, an empty list has no sum.
+impl Sum<Number> for Option<Number> {
+    fn sum<I: Iterator<Item = Number>>(iter: I) -> Self {
+        iter.reduce(Add::add)
+    }
+}
+
+impl Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Regular(v) => write!(f, "{}", v),
+            Pair(left, right) => write!(f, "[{},{}]", left, right),
+        }
+    }
+}
+
+// regular numbers are single digits, anything larger would have been split already
+fn number(input: &str) -> IResult<'_, Number> {
+    use nom::{branch::alt, character::complete::u32, combinator::verify, error::context, Parser};
+
+    let regular = context("a digit", verify(u32, |&v| v <= 9)).map(Regular);
+    alt((pair, regular))(input)
+}
+
+fn pair(input: &str) -> IResult<'_, Number> {
+    use nom::{
+        character::complete::char,
+        error::context,
+        sequence::{delimited, separated_pair},
+        Parser,
+    };
+
+    delimited(
+        context("`[`", char('[')),
+        separated_pair(number, context("`,`", char(',')), number),
+        context("`]`", char(']')),
+    )
+    .map(|(left, right)| Number::pair(left, right))
+    .parse(input)
+}
+
+impl FromStr for Number {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        crate::parse::parse_all(18, s, pair)
+    }
+}
+
+#[aoc_generator(day18)]
+pub fn input_generator(input: &str) -> Result<Vec<Number>, ParseError> {
+    use crate::parse::{lines, parse_all};
+
+    parse_all(18, input, lines(pair))
+}
+
+#[aoc(day18, part1)]
+pub fn solve_part1(numbers: &[Number]) -> Option<u64> {
+    let sum: Option<Number> = numbers.iter().cloned().sum();
+    sum.map(|n| n.magnitude())
+}
+
+#[aoc(day18, part2)]
+pub fn solve_part2(numbers: &[Number]) -> Option<u64> {
+    // addition is not commutative, so both orders of each pair are tried
+    (0..numbers.len())
+        .flat_map(|i| (0..numbers.len()).map(move |j| (i, j)))
+        .filter(|(i, j)| i != j)
+        .map(|(i, j)| (numbers[i].clone() + numbers[j].clone()).magnitude())
+        .max()
+}
+
+pub struct Day18;
+
+impl Solution for Day18 {
+    const DAY: u8 = 18;
+
+    type Input = Vec<Number>;
+    type Output1 = u64;
+    type Output2 = Maybe<u64>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        solve_part1(input).expect("homework is never empty")
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        Maybe(solve_part2(input))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static INPUT: &str = "[[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]
+[[[5,[2,8]],4],[5,[[9,9],0]]]
+[6,[[[6,2],[5,6]],[[7,6],[4,7]]]]
+[[[6,[0,7]],[0,9]],[4,[9,[9,0]]]]
+[[[7,[6,4]],[3,[1,3]]],[[[5,5],1],9]]
+[[6,[[7,3],[3,2]]],[[[3,8],[5,7]],4]]
+[[[[5,4],[7,7]],8],[[8,3],8]]
+[[9,3],[[9,9],[6,[4,9]]]]
+[[2,[[7,7],7]],[[5,8],[[9,3],[0,2]]]]
+[[[[5,2],5],[8,[3,7]]],[[5,[7,5]],[4,4]]]";
+
+    fn num(s: &str) -> Number {
+        s.parse().unwrap()
+    }
+
+    fn sum(s: &str) -> Number {
+        let sum: Option<Number> = input_generator(s).unwrap().into_iter().sum();
+        sum.unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        for s in [
+            "[1,2]",
+            "[[1,2],3]",
+            "[[[[1,3],[5,3]],[[1,3],[8,7]]],[[[4,9],[6,9]],[[8,2],[7,3]]]]",
+        ] {
+            assert_eq!(num(s).to_string(), s);
+        }
+
+        let err = "[1,2".parse::<Number>().unwrap_err();
+        assert_eq!((err.line, err.column, err.expected.as_str()), (1, 5, "`]`"));
+        assert!("7".parse::<Number>().is_err());
+
+        let err = "[4294967295,[[[[1,1],1],1],1]]"
+            .parse::<Number>()
+            .unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.expected.as_str()),
+            (1, 2, "a digit")
+        );
+    }
+
+    #[test]
+    fn test_explode() {
+        for (before, after) in [
+            ("[[[[[9,8],1],2],3],4]", "[[[[0,9],2],3],4]"),
+            ("[7,[6,[5,[4,[3,2]]]]]", "[7,[6,[5,[7,0]]]]"),
+            ("[[6,[5,[4,[3,2]]]],1]", "[[6,[5,[7,0]]],3]"),
+            (
+                "[[3,[2,[1,[7,3]]]],[6,[5,[4,[3,2]]]]]",
+                "[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]",
+            ),
+            (
+                "[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]",
+                "[[3,[2,[8,0]]],[9,[5,[7,0]]]]",
+            ),
+        ] {
+            let mut n = num(before);
+            assert!(n.explode());
+            assert_eq!(n.to_string(), after);
+        }
+    }
+
+    #[test]
+    fn test_split() {
+        for (before, after) in [(10, "[5,5]"), (11, "[5,6]"), (12, "[6,6]")] {
+            let mut n = Regular(before);
+            assert!(n.split());
+            assert_eq!(n.to_string(), after);
+        }
+    }
+
+    #[test]
+    fn test_reduce_steps() {
+        let mut n = num("[[[[4,3],4],4],[7,[[8,4],9]]]") + num("[1,1]");
+        assert_eq!(n.to_string(), "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]");
+
+        n = num("[[[[[4,3],4],4],[7,[[8,4],9]]],[1,1]]");
+        for step in [
+            "[[[[0,7],4],[7,[[8,4],9]]],[1,1]]",
+            "[[[[0,7],4],[15,[0,13]]],[1,1]]",
+            "[[[[0,7],4],[[7,8],[0,13]]],[1,1]]",
+            "[[[[0,7],4],[[7,8],[0,[6,7]]]],[1,1]]",
+            "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]",
+        ] {
+            assert!(n.reduce_step());
+            assert_eq!(n.to_string(), step);
+        }
+        assert!(!n.reduce_step());
+    }
+
+    #[test]
+    fn test_sum() {
+        assert_eq!(
+            sum("[1,1]\n[2,2]\n[3,3]\n[4,4]").to_string(),
+            "[[[[1,1],[2,2]],[3,3]],[4,4]]"
+        );
+        assert_eq!(
+            sum("[1,1]\n[2,2]\n[3,3]\n[4,4]\n[5,5]").to_string(),
+            "[[[[3,0],[5,3]],[4,4]],[5,5]]"
+        );
+        assert_eq!(
+            sum("[1,1]\n[2,2]\n[3,3]\n[4,4]\n[5,5]\n[6,6]").to_string(),
+            "[[[[5,0],[7,4]],[5,5]],[6,6]]"
+        );
+
+        let mut n = num("[[[0,[4,5]],[0,0]],[[[4,5],[2,6]],[9,5]]]");
+        for (term, total) in [
+            (
+                "[7,[[[3,7],[4,3]],[[6,3],[8,8]]]]",
+                "[[[[4,0],[5,4]],[[7,7],[6,0]]],[[8,[7,7]],[[7,9],[5,0]]]]",
+            ),
+            (
+                "[[2,[[0,8],[3,4]]],[[[6,7],1],[7,[1,6]]]]",
+                "[[[[6,7],[6,7]],[[7,7],[0,7]]],[[[8,7],[7,7]],[[8,8],[8,0]]]]",
+            ),
+            (
+                "[[[[2,4],7],[6,[0,5]]],[[[6,8],[2,8]],[[2,1],[4,5]]]]",
+                "[[[[7,0],[7,7]],[[7,7],[7,8]]],[[[7,7],[8,8]],[[7,7],[8,7]]]]",
+            ),
+            (
+                "[7,[5,[[3,8],[1,4]]]]",
+                "[[[[7,7],[7,8]],[[9,5],[8,7]]],[[[6,8],[0,8]],[[9,9],[9,0]]]]",
+            ),
+            (
+                "[[2,[2,2]],[8,[8,1]]]",
+                "[[[[6,6],[6,6]],[[6,0],[6,7]]],[[[7,7],[8,9]],[8,[8,1]]]]",
+            ),
+            ("[2,9]", "[[[[6,6],[7,7]],[[0,7],[7,7]]],[[[5,5],[5,6]],9]]"),
+            (
+                "[1,[[[9,3],9],[[9,0],[0,7]]]]",
+                "[[[[7,8],[6,7]],[[6,8],[0,8]]],[[[7,7],[5,0]],[[5,5],[5,6]]]]",
+            ),
+            (
+                "[[[5,[7,4]],7],1]",
+                "[[[[7,7],[7,7]],[[8,7],[8,7]]],[[[7,0],[7,7]],9]]",
+            ),
+            (
+                "[[[[4,2],2],6],[8,7]]",
+                "[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]",
+            ),
+        ] {
+            n = n + num(term);
+            assert_eq!(n.to_string(), total);
+        }
+
+        assert_eq!(
+            sum(INPUT).to_string(),
+            "[[[[6,6],[7,6]],[[7,7],[7,0]]],[[[7,7],[7,7]],[[7,8],[9,9]]]]"
+        );
+    }
+
+    #[test]
+    fn test_magnitude() {
+        for (n, magnitude) in [
+            ("[[1,2],[[3,4],5]]", 143),
+            ("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]", 1384),
+            ("[[[[1,1],[2,2]],[3,3]],[4,4]]", 445),
+            ("[[[[3,0],[5,3]],[4,4]],[5,5]]", 791),
+            ("[[[[5,0],[7,4]],[5,5]],[6,6]]", 1137),
+            (
+                "[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]",
+                3488,
+            ),
+        ] {
+            assert_eq!(num(n).magnitude(), magnitude);
+        }
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(solve_part1(&input_generator(INPUT).unwrap()), Some(4140))
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(solve_part2(&input_generator(INPUT).unwrap()), Some(3993))
+    }
+}
@@ -0,0 +1,509 @@
+use super::*;
+use crate::solution::Maybe;
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+
+pub type Point = [i32; 3];
+
+/// Two scanners overlap when they both see at least this many beacons.
+const OVERLAP: usize = 12;
+
+fn add(a: Point, b: Point) -> Point {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn sub(a: Point, b: Point) -> Point {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn manhattan(a: Point, b: Point) -> i32 {
+    sub(a, b).iter().map(|d| d.abs()).sum()
+}
+
+/// One of the 24 orientations a scanner can be in, as a permutation of the axes with signs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rotation {
+    axes: [usize; 3],
+    signs: [i32; 3],
+}
+
+impl Rotation {
+    pub fn all() -> impl Iterator<Item = Rotation> {
+        // the sign of the last axis is fixed by the others, mirror images are not rotations
+        const PERMUTATIONS: [([usize; 3], i32); 6] = [
+            ([0, 1, 2], 1),
+            ([1, 2, 0], 1),
+            ([2, 0, 1], 1),
+            ([0, 2, 1], -1),
+            ([2, 1, 0], -1),
+            ([1, 0, 2], -1),
+        ];
+
+        PERMUTATIONS.into_iter().flat_map(|(axes, parity)| {
+            [(1, 1), (1, -1), (-1, 1), (-1, -1)]
+                .into_iter()
+                .map(move |(a, b)| Rotation {
+                    axes,
+                    signs: [a, b, parity * a * b],
+                })
+        })
+    }
+
+    pub fn apply(&self, p: Point) -> Point {
+        [0, 1, 2].map(|i| self.signs[i] * p[self.axes[i]])
+    }
+}
+
+#[derive(Debug)]
+pub struct Scanner {
+    pub id: u32,
+    beacons: Vec<Point>,
+    // sorted squared distances between all of its beacons, these do not depend on the orientation
+    fingerprint: Vec<i64>,
+}
+
+impl Scanner {
+    pub fn new(id: u32, beacons: Vec<Point>) -> Self {
+        let mut fingerprint: Vec<_> = beacons
+            .iter()
+            .tuple_combinations()
+            .map(|(&a, &b)| sub(a, b).iter().map(|&d| d as i64 * d as i64).sum())
+            .collect();
+        fingerprint.sort_unstable();
+
+        Self {
+            id,
+            beacons,
+            fingerprint,
+        }
+    }
+
+    pub fn beacons(&self) -> &[Point] {
+        &self.beacons
+    }
+
+    // number of distances both scanners measured, counting duplicates
+    fn shared_distances(&self, other: &Scanner) -> usize {
+        let (mut a, mut b) = (self.fingerprint.iter(), other.fingerprint.iter());
+        let (mut x, mut y) = (a.next(), b.next());
+        let mut shared = 0;
+
+        while let (Some(dx), Some(dy)) = (x, y) {
+            match dx.cmp(dy) {
+                std::cmp::Ordering::Less => x = a.next(),
+                std::cmp::Ordering::Greater => y = b.next(),
+                std::cmp::Ordering::Equal => {
+                    shared += 1;
+                    x = a.next();
+                    y = b.next();
+                }
+            }
+        }
+
+        shared
+    }
+
+    /// Position and orientation of this scanner relative to `known` beacons, if enough of them
+    /// are in sight.
+    pub fn locate(&self, known: &[Point]) -> Option<(Point, Rotation)> {
+        for rotation in Rotation::all() {
+            let mut offsets = HashMap::new();
+            for &a in known {
+                for &b in &self.beacons {
+                    let offset = sub(a, rotation.apply(b));
+                    let count = offsets.entry(offset).or_insert(0);
+                    *count += 1;
+                    if *count >= OVERLAP {
+                        return Some((offset, rotation));
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Beacons and scanner positions relative to the first scanner.
+#[derive(Debug)]
+pub struct Alignment {
+    pub beacons: HashSet<Point>,
+    pub scanners: Vec<Point>,
+}
+
+impl Alignment {
+    pub fn max_distance(&self) -> i32 {
+        self.scanners
+            .iter()
+            .tuple_combinations()
+            .map(|(&a, &b)| manhattan(a, b))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// Aligns all scanners to the first one, or `None` if some scanner overlaps with no other.
+pub fn align(scanners: &[Scanner]) -> Option<Alignment> {
+    // beacons of the already aligned scanners, in the coordinates of the first one
+    let mut placed: Vec<Option<Vec<Point>>> = vec![None; scanners.len()];
+    let mut positions = vec![[0; 3]; scanners.len()];
+
+    placed[0] = Some(scanners.first()?.beacons.clone());
+    let mut queue = vec![0];
+
+    // every pair of the overlapping beacons contributes a shared distance
+    let min_shared = OVERLAP * (OVERLAP - 1) / 2;
+
+    while let Some(i) = queue.pop() {
+        for j in 0..scanners.len() {
+            if placed[j].is_some() || scanners[i].shared_distances(&scanners[j]) < min_shared {
+                continue;
+            }
+
+            let known = placed[i].as_ref().unwrap();
+            if let Some((offset, rotation)) = scanners[j].locate(known) {
+                let beacons = scanners[j]
+                    .beacons
+                    .iter()
+                    .map(|&b| add(rotation.apply(b), offset))
+                    .collect();
+                placed[j] = Some(beacons);
+                positions[j] = offset;
+                queue.push(j);
+            }
+        }
+    }
+
+    let beacons = placed
+        .into_iter()
+        .collect::<Option<Vec<_>>>()?
+        .into_iter()
+        .flatten()
+        .collect();
+
+    Some(Alignment {
+        beacons,
+        scanners: positions,
+    })
+}
+
+#[aoc_generator(day19)]
+pub fn input_generator(input: &str) -> Result<Vec<Scanner>, ParseError> {
+    use crate::parse::{blocks, lines, parse_all};
+    use nom::{
+        bytes::complete::tag,
+        character::complete::{char, i32, line_ending, u32},
+        error::context,
+        sequence::{delimited, preceded, separated_pair, tuple},
+        Parser,
+    };
+
+    let header = delimited(
+        context("`--- scanner `", tag("--- scanner ")),
+        context("a scanner number", u32),
+        context("` ---`", tag(" ---")),
+    );
+    let coordinate = || context("a coordinate", i32);
+    let point = tuple((
+        coordinate(),
+        preceded(char(','), coordinate()),
+        preceded(char(','), coordinate()),
+    ))
+    .map(|(x, y, z)| [x, y, z]);
+    let scanner = separated_pair(header, line_ending, lines(point))
+        .map(|(id, beacons)| Scanner::new(id, beacons));
+
+    parse_all(19, input, blocks(scanner))
+}
+
+#[aoc(day19, part1)]
+pub fn solve_part1(scanners: &[Scanner]) -> Option<usize> {
+    align(scanners).map(|alignment| alignment.beacons.len())
+}
+
+#[aoc(day19, part2)]
+pub fn solve_part2(scanners: &[Scanner]) -> Option<i32> {
+    align(scanners).map(|alignment| alignment.max_distance())
+}
+
+pub struct Day19;
+
+impl Solution for Day19 {
+    const DAY: u8 = 19;
+
+    type Input = Vec<Scanner>;
+    type Output1 = Maybe<usize>;
+    type Output2 = Maybe<i32>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        Maybe(solve_part1(input))
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        Maybe(solve_part2(input))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::Rng;
+
+    static INPUT: &str = "--- scanner 0 ---
+404,-588,-901
+528,-643,409
+-838,591,734
+390,-675,-793
+-537,-823,-458
+-485,-357,347
+-345,-311,381
+-661,-816,-575
+-876,649,763
+-618,-824,-621
+553,345,-567
+474,580,667
+-447,-329,318
+-584,868,-557
+544,-627,-890
+564,392,-477
+455,729,728
+-892,524,684
+-689,845,-530
+423,-701,434
+7,-33,-71
+630,319,-379
+443,580,662
+-789,900,-551
+459,-707,401
+
+--- scanner 1 ---
+686,422,578
+605,423,415
+515,917,-361
+-336,658,858
+95,138,22
+-476,619,847
+-340,-569,-846
+567,-361,727
+-460,603,-452
+669,-402,600
+729,430,532
+-500,-761,534
+-322,571,750
+-466,-666,-811
+-429,-592,574
+-355,545,-477
+703,-491,-529
+-328,-685,520
+413,935,-424
+-391,539,-444
+586,-435,557
+-364,-763,-893
+807,-499,-711
+755,-354,-619
+553,889,-390
+
+--- scanner 2 ---
+649,640,665
+682,-795,504
+-784,533,-524
+-644,584,-595
+-588,-843,648
+-30,6,44
+-674,560,763
+500,723,-460
+609,671,-379
+-555,-800,653
+-675,-892,-343
+697,-426,-610
+578,704,681
+493,664,-388
+-671,-858,530
+-667,343,800
+571,-461,-707
+-138,-166,112
+-889,563,-600
+646,-828,498
+640,759,510
+-630,509,768
+-681,-892,-333
+673,-379,-804
+-742,-814,-386
+577,-820,562
+
+--- scanner 3 ---
+-589,542,597
+605,-692,669
+-500,565,-823
+-660,373,557
+-458,-679,-417
+-488,449,543
+-626,468,-788
+338,-750,-386
+528,-832,-391
+562,-778,733
+-938,-730,414
+543,643,-506
+-524,371,-870
+407,773,750
+-104,29,83
+378,-903,-323
+-778,-728,485
+426,699,580
+-438,-605,-362
+-469,-447,-387
+509,732,623
+647,635,-688
+-868,-804,481
+614,-800,639
+595,780,-596
+
+--- scanner 4 ---
+727,592,562
+-293,-554,779
+441,611,-461
+-714,465,-776
+-743,427,-804
+-660,-479,-426
+832,-632,460
+927,-485,-438
+408,393,-506
+466,436,-512
+110,16,151
+-258,-428,682
+-393,719,612
+-211,-452,876
+808,-476,-593
+-575,615,604
+-485,667,467
+-680,325,-822
+-627,-443,-432
+872,-547,-609
+833,512,582
+807,604,487
+839,-516,451
+891,-625,532
+-652,-548,-490
+30,-46,-14";
+
+    static POSITIONS: [Point; 4] = [
+        [0, 0, 0],
+        [1100, -150, 80],
+        [2100, 120, -60],
+        [1050, 300, 900],
+    ];
+
+    // pseudo random beacons around the scanners
+    fn world() -> Vec<Point> {
+        let mut rng = Rng::new(0x2021);
+        (0..220)
+            .map(|_| {
+                [
+                    rng.range(-1000..3100),
+                    rng.range(-1100..1900),
+                    rng.range(-1000..2100),
+                ]
+            })
+            .collect()
+    }
+
+    fn in_range(scanner: Point, beacon: Point) -> bool {
+        sub(beacon, scanner).iter().all(|d| d.abs() <= 1000)
+    }
+
+    // reports of the scanners at `positions`, each in an orientation of its own
+    fn reports(beacons: &[Point], positions: &[Point]) -> String {
+        positions
+            .iter()
+            .enumerate()
+            .map(|(i, &scanner)| {
+                let rotation = Rotation::all().nth(i * 7 % 24).unwrap();
+                let seen = beacons
+                    .iter()
+                    .filter(|&&b| in_range(scanner, b))
+                    .map(|&b| rotation.apply(sub(b, scanner)))
+                    .map(|[x, y, z]| format!("{},{},{}", x, y, z))
+                    .join("\n");
+                format!("--- scanner {} ---\n{}", i, seen)
+            })
+            .join("\n\n")
+    }
+
+    #[test]
+    fn test_rotations() {
+        let images: HashSet<_> = Rotation::all().map(|r| r.apply([1, 2, 3])).collect();
+        assert_eq!(images.len(), 24);
+
+        // a rotation keeps the handedness of the axes
+        for r in Rotation::all() {
+            let [x, y, z] = [[1, 0, 0], [0, 1, 0], [0, 0, 1]].map(|p| r.apply(p));
+            let cross = [
+                x[1] * y[2] - x[2] * y[1],
+                x[2] * y[0] - x[0] * y[2],
+                x[0] * y[1] - x[1] * y[0],
+            ];
+            assert_eq!(cross, z);
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let scanners = input_generator(
+            "--- scanner 0 ---\n0,2,0\n4,1,0\n3,3,0\n\n--- scanner 1 ---\n-1,-1,0\n-5,0,0\n-2,1,0",
+        )
+        .unwrap();
+        assert_eq!(scanners.len(), 2);
+        assert_eq!(scanners[1].beacons(), [[-1, -1, 0], [-5, 0, 0], [-2, 1, 0]]);
+        assert_eq!(scanners[0].shared_distances(&scanners[1]), 3);
+
+        let err = input_generator("--- scanner 0 ---\n0,2\n").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 4));
+    }
+
+    #[test]
+    fn test_align() {
+        let world = world();
+        let scanners = input_generator(&reports(&world, &POSITIONS)).unwrap();
+        let alignment = align(&scanners).unwrap();
+
+        let seen: HashSet<_> = world
+            .into_iter()
+            .filter(|&b| POSITIONS.iter().any(|&s| in_range(s, b)))
+            .collect();
+        assert_eq!(alignment.beacons, seen);
+        assert_eq!(alignment.scanners, POSITIONS);
+        assert_eq!(alignment.max_distance(), 2280);
+    }
+
+    #[test]
+    fn test_no_overlap() {
+        let world = world();
+        let input = reports(&world, &[POSITIONS[0], POSITIONS[2]]);
+        assert_eq!(solve_part1(&input_generator(&input).unwrap()), None);
+    }
+
+    #[test]
+    fn test_part1() {
+        let scanners = input_generator(INPUT).unwrap();
+        assert_eq!(
+            align(&scanners).unwrap().scanners,
+            [
+                [0, 0, 0],
+                [68, -1246, -43],
+                [1105, -1205, 1229],
+                [-92, -2380, -20],
+                [-20, -1133, 1061]
+            ]
+        );
+        assert_eq!(solve_part1(&scanners), Some(79))
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(solve_part2(&input_generator(INPUT).unwrap()), Some(3621))
+    }
+}
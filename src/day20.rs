@@ -1,88 +1,52 @@
 use super::*;
-use ndarray::prelude::*;
+use crate::grid::Grid;
 
 #[derive(Debug)]
 pub struct Input {
     table: Vec<bool>,
-    image: Array2<bool>,
+    image: Grid<bool>,
 }
 
 #[aoc_generator(day20)]
-pub fn input_generator(input: &str) -> Result<Input, nom::Err<()>> {
-    use nom::{
-        character::complete::{char, line_ending},
-        multi::{many1, separated_list1},
-        Parser,
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    use crate::{
+        grid::{cell, grid},
+        parse::{parse_all, sections},
     };
+    use nom::{combinator::verify, error::context, multi::many1, Parser};
 
-    let element = |i| {
-        char('.')
-            .map(|_| false)
-            .or(char('#').map(|_| true))
-            .parse(i)
-    };
-    let table = many1(element);
-    let arr = separated_list1(line_ending, many1(element)).map(|arr| {
-        let rows = arr.len();
-        let cols = arr[0].len();
-        Array2::from_shape_vec((rows, cols), arr.into_iter().flatten().collect()).unwrap()
-    });
-    let mut parser = table
-        .and(line_ending.and(line_ending))
-        .and(arr)
-        .map(|((table, _), image)| Input { table, image });
-    parser.parse(input).map(|(_, i)| i)
+    let table = context(
+        "an enhancement table of 512 pixels",
+        verify(many1(cell), |table: &[bool]| table.len() == 512),
+    );
+    let parser = sections(table, grid).map(|(table, image)| Input { table, image });
+
+    parse_all(20, input, parser)
 }
 
 fn enhance_image(
-    input_image: ArrayView2<bool>,
+    input_image: &Grid<bool>,
     enhancement_table: &[bool],
     outside: bool,
-) -> Array2<bool> {
-    let dims = input_image.dim();
-    let mut output_image = Array2::from_elem(dims, false);
-
-    for ((i, j), v) in output_image.indexed_iter_mut() {
-        let idx = [
-            (i.checked_sub(1), j.checked_sub(1)),
-            (i.checked_sub(1), Some(j)),
-            (i.checked_sub(1), j.checked_add(1)),
-            (Some(i), j.checked_sub(1)),
-            (Some(i), Some(j)),
-            (Some(i), j.checked_add(1)),
-            (i.checked_add(1), j.checked_sub(1)),
-            (i.checked_add(1), Some(j)),
-            (i.checked_add(1), j.checked_add(1)),
-        ]
-        .into_iter()
-        .fold(0, |mut value, (x, y)| {
-            value <<= 1;
-
-            let bit = match (x, y) {
-                (Some(x), Some(y)) => input_image.get((x, y)).copied().unwrap_or(outside),
-                _ => outside,
-            };
-
-            if bit {
-                value |= 1;
-            }
-
-            value
+) -> Grid<bool> {
+    let mut output_image = input_image.clone();
+
+    for pos in input_image.positions() {
+        let idx = input_image.neighborhood(pos).fold(0, |value, bit| {
+            value << 1 | *bit.unwrap_or(&outside) as usize
         });
 
-        *v = enhancement_table[idx];
+        output_image[pos] = enhancement_table[idx];
     }
 
     output_image
 }
 
-fn calculate_step(mut img: Array2<bool>, enhancement_table: &[bool], step: usize) -> Array2<bool> {
+fn calculate_step(mut img: Grid<bool>, enhancement_table: &[bool], step: usize) -> Grid<bool> {
     for step in 0..step {
         let outside = enhancement_table[0] && step % 2 != 0;
-        let (rows, cols) = img.dim();
-        let mut image = Array2::from_elem((rows + 2, cols + 2), outside);
-        image.slice_mut(s![1..-1, 1..-1]).assign(&img);
-        img = enhance_image(image.view(), enhancement_table, outside);
+        let image = img.pad(1, outside);
+        img = enhance_image(&image, enhancement_table, outside);
     }
 
     img
@@ -104,6 +68,28 @@ pub fn solve_part2(input: &Input) -> usize {
         .count()
 }
 
+pub struct Day20;
+
+impl Solution for Day20 {
+    const DAY: u8 = 20;
+
+    type Input = Input;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        solve_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        solve_part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
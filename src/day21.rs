@@ -0,0 +1,218 @@
+use super::*;
+use crate::solution::Maybe;
+use std::collections::HashMap;
+
+const BOARD_SIZE: u32 = 10;
+
+// number of ways to roll each sum with three rolls of the three sided Dirac die
+const DIRAC_ROLLS: [u128; 10] = {
+    let mut freq = [0; 10];
+    let mut i = 0;
+    while i < 27 {
+        freq[i / 9 + i / 3 % 3 + i % 3 + 3] += 1;
+        i += 1;
+    }
+    freq
+};
+
+#[aoc_generator(day21)]
+pub fn input_generator(input: &str) -> Result<[u32; 2], ParseError> {
+    use crate::parse::parse_all;
+    use nom::{
+        bytes::complete::tag,
+        character::complete::{line_ending, u32},
+        combinator::verify,
+        error::context,
+        sequence::{preceded, separated_pair},
+        Parser,
+    };
+
+    let start = |player| {
+        preceded(
+            context("`Player N starting position: `", tag(player)),
+            context(
+                "a position on the board",
+                verify(u32, |&p| (1..=BOARD_SIZE).contains(&p)),
+            ),
+        )
+    };
+    let parser = separated_pair(
+        start("Player 1 starting position: "),
+        line_ending,
+        start("Player 2 starting position: "),
+    )
+    .map(|(a, b)| [a, b]);
+
+    parse_all(21, input, parser)
+}
+
+// the start positions counted from 0, `None` if they are not on the board
+fn positions(start: [u32; 2], board: u32) -> Option<[u32; 2]> {
+    start
+        .iter()
+        .all(|p| (1..=board).contains(p))
+        .then(|| start.map(|p| p - 1))
+}
+
+/// Plays with the deterministic 100-sided die until someone reaches `target` points, returns the
+/// score of the losing player times the number of rolls.
+///
+/// Returns `None` if a start position is not on the board or the result does not fit a `u32`.
+pub fn deterministic(start: [u32; 2], board: u32, target: u32) -> Option<u32> {
+    let mut positions = positions(start, board)?;
+    let mut scores = [0; 2];
+    let mut rolls = 0;
+
+    for player in [0, 1].into_iter().cycle() {
+        let moves: u32 = (0..3).map(|i| (rolls + i) % 100 + 1).sum();
+        rolls += 3;
+
+        positions[player] = (positions[player] + moves) % board;
+        scores[player] += positions[player] + 1;
+        if scores[player] >= target {
+            return scores[1 - player].checked_mul(rolls);
+        }
+    }
+
+    unreachable!()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct State {
+    positions: [u32; 2],
+    scores: [u32; 2],
+    turn: usize,
+}
+
+// number of universes each player wins in, starting from `state`, `None` once a count overflows
+fn wins(
+    state: State,
+    board: u32,
+    target: u32,
+    memo: &mut HashMap<State, [u128; 2]>,
+) -> Option<[u128; 2]> {
+    if let Some(&wins) = memo.get(&state) {
+        return Some(wins);
+    }
+
+    let player = state.turn;
+    let mut total = [0u128; 2];
+    for (moves, &freq) in DIRAC_ROLLS.iter().enumerate().filter(|(_, &f)| f > 0) {
+        let mut next = state;
+        next.positions[player] = (next.positions[player] + moves as u32) % board;
+        next.scores[player] += next.positions[player] + 1;
+
+        if next.scores[player] >= target {
+            total[player] = total[player].checked_add(freq)?;
+        } else {
+            next.turn = 1 - player;
+            let won = wins(next, board, target, memo)?;
+            for (total, won) in total.iter_mut().zip(won) {
+                *total = total.checked_add(freq.checked_mul(won)?)?;
+            }
+        }
+    }
+
+    memo.insert(state, total);
+    Some(total)
+}
+
+/// Number of universes each player wins in when playing with the Dirac die up to `target` points.
+///
+/// Returns `None` if a start position is not on the board or the counts do not fit a `u128`.
+pub fn quantum(start: [u32; 2], board: u32, target: u32) -> Option<[u128; 2]> {
+    let state = State {
+        positions: positions(start, board)?,
+        scores: [0; 2],
+        turn: 0,
+    };
+    wins(state, board, target, &mut HashMap::new())
+}
+
+#[aoc(day21, part1)]
+pub fn solve_part1(start: &[u32; 2]) -> Option<u32> {
+    deterministic(*start, BOARD_SIZE, 1000)
+}
+
+#[aoc(day21, part2)]
+pub fn solve_part2(start: &[u32; 2]) -> Option<u128> {
+    let [a, b] = quantum(*start, BOARD_SIZE, 21)?;
+    Some(a.max(b))
+}
+
+pub struct Day21;
+
+impl Solution for Day21 {
+    const DAY: u8 = 21;
+
+    type Input = [u32; 2];
+    type Output1 = Maybe<u32>;
+    type Output2 = Maybe<u128>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        Maybe(solve_part1(input))
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        Maybe(solve_part2(input))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static INPUT: &str = "Player 1 starting position: 4\nPlayer 2 starting position: 8";
+
+    #[test]
+    fn test_dirac_rolls() {
+        assert_eq!(DIRAC_ROLLS, [0, 0, 0, 1, 3, 6, 7, 6, 3, 1]);
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(solve_part1(&input_generator(INPUT).unwrap()), Some(739785))
+    }
+
+    #[test]
+    fn test_part2() {
+        let start = input_generator(INPUT).unwrap();
+        assert_eq!(
+            quantum(start, BOARD_SIZE, 21),
+            Some([444356092776315, 341960390180808])
+        );
+        assert_eq!(solve_part2(&start), Some(444356092776315))
+    }
+
+    #[test]
+    fn test_variants() {
+        // the first player always wins right away
+        assert_eq!(quantum([4, 8], BOARD_SIZE, 1), Some([27, 0]));
+        // on a board of one space every roll scores a single point
+        assert_eq!(deterministic([1, 1], 1, 3), Some(2 * 15));
+        assert_eq!(quantum([1, 1], 1, 2), Some([27 * 27 * 27, 0]));
+
+        // more universes than a u64 can count
+        let [a, b] = quantum([4, 8], BOARD_SIZE, 30).unwrap();
+        assert!(a.max(b) > u64::MAX as u128);
+        assert_eq!(quantum([4, 8], BOARD_SIZE, 60), None);
+
+        assert_eq!(deterministic([4, 8], 0, 1000), None);
+        assert_eq!(quantum([4, 11], BOARD_SIZE, 21), None);
+        assert_eq!(deterministic([0, 8], BOARD_SIZE, 1000), None);
+    }
+
+    #[test]
+    fn test_parse() {
+        let err = input_generator("Player 1 starting position: 4\nPlayer 2 starting position: 11")
+            .unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.expected.as_str()),
+            (2, 29, "a position on the board")
+        );
+    }
+}
@@ -0,0 +1,332 @@
+use super::*;
+use crate::solution::Maybe;
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+};
+
+const ROOMS: usize = 4;
+const MAX_DEPTH: usize = 8;
+const ENERGY: [u32; ROOMS] = [1, 10, 100, 1000];
+
+// amphipods never stop on the space right outside of a room
+const STOPS: [usize; 7] = [0, 1, 3, 5, 7, 9, 10];
+
+// rows inserted into the rooms for part 2
+const FOLDED: [[usize; ROOMS]; 2] = [[3, 2, 1, 0], [3, 1, 0, 2]];
+
+fn door(room: usize) -> usize {
+    2 + 2 * room
+}
+
+/// The amphipods in the rooms of a burrow with an empty hallway, `rows[0]` is the top row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Burrow {
+    rows: Vec<[usize; ROOMS]>,
+}
+
+/// Contents of every space packed into three bits each, the hallway stops come first and the
+/// rooms follow from top to bottom.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct State(u128);
+
+impl State {
+    fn get(self, space: usize) -> Option<usize> {
+        match (self.0 >> (3 * space)) & 0b111 {
+            0 => None,
+            a => Some(a as usize - 1),
+        }
+    }
+
+    fn set(self, space: usize, amphipod: Option<usize>) -> State {
+        let bits = amphipod.map_or(0, |a| a as u128 + 1);
+        State(self.0 & !(0b111 << (3 * space)) | bits << (3 * space))
+    }
+}
+
+struct Search {
+    depth: usize,
+}
+
+impl Search {
+    fn space(&self, room: usize, row: usize) -> usize {
+        STOPS.len() + room * self.depth + row
+    }
+
+    // whether the hallway between `from` and `to` is empty, not counting `from` itself
+    fn clear(&self, state: State, from: usize, to: usize) -> bool {
+        let range = from.min(to)..=from.max(to);
+        STOPS
+            .iter()
+            .enumerate()
+            .filter(|&(_, &x)| x != from && range.contains(&x))
+            .all(|(stop, _)| state.get(stop).is_none())
+    }
+
+    // whether a room only holds amphipods that belong there
+    fn settled(&self, state: State, room: usize) -> bool {
+        (0..self.depth).all(|row| state.get(self.space(room, row)).is_none_or(|a| a == room))
+    }
+
+    fn moves(&self, state: State) -> Vec<(State, u32)> {
+        // moving into the destination room is never worse than anything else, so it is taken
+        // right away whenever possible
+        for (stop, &x) in STOPS.iter().enumerate() {
+            let Some(a) = state.get(stop) else {
+                continue;
+            };
+            if !self.settled(state, a) || !self.clear(state, x, door(a)) {
+                continue;
+            }
+            if let Some(row) = (0..self.depth)
+                .rev()
+                .find(|&row| state.get(self.space(a, row)).is_none())
+            {
+                let steps = x.abs_diff(door(a)) + row + 1;
+                let next = state.set(stop, None).set(self.space(a, row), Some(a));
+                return vec![(next, steps as u32 * ENERGY[a])];
+            }
+        }
+
+        let mut moves = Vec::new();
+        for room in (0..ROOMS).filter(|&room| !self.settled(state, room)) {
+            let Some((row, a)) =
+                (0..self.depth).find_map(|row| Some((row, state.get(self.space(room, row))?)))
+            else {
+                continue;
+            };
+
+            for (stop, &x) in STOPS.iter().enumerate() {
+                if state.get(stop).is_none() && self.clear(state, door(room), x) {
+                    let steps = door(room).abs_diff(x) + row + 1;
+                    let next = state.set(self.space(room, row), None).set(stop, Some(a));
+                    moves.push((next, steps as u32 * ENERGY[a]));
+                }
+            }
+        }
+        moves
+    }
+}
+
+impl Burrow {
+    pub fn depth(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// The burrow with `rows` inserted into the rooms before row `at`, if the rooms are not
+    /// getting too deep.
+    pub fn insert_rows(&self, at: usize, rows: &[[usize; ROOMS]]) -> Option<Burrow> {
+        if self.depth() + rows.len() > MAX_DEPTH {
+            return None;
+        }
+
+        let mut burrow = self.clone();
+        burrow.rows.splice(at..at, rows.iter().copied());
+        Some(burrow)
+    }
+
+    fn state(&self) -> State {
+        let search = Search {
+            depth: self.depth(),
+        };
+        let mut state = State(0);
+        for (row, amphipods) in self.rows.iter().enumerate() {
+            for (room, &a) in amphipods.iter().enumerate() {
+                state = state.set(search.space(room, row), Some(a));
+            }
+        }
+        state
+    }
+
+    /// Least energy needed to sort all amphipods into their rooms.
+    pub fn min_energy(&self) -> Option<u32> {
+        let search = Search {
+            depth: self.depth(),
+        };
+        let goal = Burrow {
+            rows: vec![[0, 1, 2, 3]; self.depth()],
+        }
+        .state();
+
+        let mut energy = HashMap::new();
+        let mut queue = BinaryHeap::new();
+        energy.insert(self.state(), 0);
+        queue.push(Reverse((0, self.state())));
+
+        while let Some(Reverse((spent, state))) = queue.pop() {
+            if state == goal {
+                return Some(spent);
+            }
+            if spent > energy[&state] {
+                continue;
+            }
+
+            for (next, cost) in search.moves(state) {
+                let next_spent = spent + cost;
+                if energy.get(&next).is_none_or(|&e| next_spent < e) {
+                    energy.insert(next, next_spent);
+                    queue.push(Reverse((next_spent, next)));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+#[aoc_generator(day23)]
+pub fn input_generator(input: &str) -> Result<Burrow, ParseError> {
+    use crate::parse::parse_all;
+    use nom::{
+        bytes::complete::tag,
+        character::complete::{char, line_ending, satisfy, space0},
+        combinator::{consumed, verify},
+        error::context,
+        multi::many0,
+        sequence::{delimited, preceded, terminated, tuple},
+        Parser,
+    };
+
+    let amphipod = || {
+        context(
+            "an amphipod",
+            satisfy(|c| ('A'..='D').contains(&c)).map(|c| c as usize - 'A' as usize),
+        )
+    };
+    let wall = || context("`#`", char('#'));
+    let row = || {
+        tuple((
+            wall(),
+            amphipod(),
+            wall(),
+            amphipod(),
+            wall(),
+            amphipod(),
+            wall(),
+            amphipod(),
+            wall(),
+        ))
+        .map(|(_, a, _, b, _, c, _, d, _)| [a, b, c, d])
+    };
+
+    let top = tuple((
+        context("a wall", tag("#############")),
+        line_ending,
+        context("an empty hallway", tag("#...........#")),
+        line_ending,
+    ));
+    let first = delimited(
+        context("`##`", tag("##")),
+        row(),
+        context("`##`", tag("##")),
+    );
+    let below = preceded(
+        line_ending,
+        delimited(context("`  `", tag("  ")), row(), space0),
+    );
+    let rows = context(
+        "at most 8 rows of amphipods",
+        verify(first.and(many0(below)), |(_, below): &(_, Vec<_>)| {
+            below.len() < MAX_DEPTH
+        }),
+    )
+    .map(|(first, mut below)| {
+        below.insert(0, first);
+        below
+    });
+    let bottom = preceded(
+        line_ending,
+        preceded(tag("  "), context("a wall", tag("#########"))),
+    );
+
+    let parser = preceded(top, terminated(consumed(rows), bottom));
+    let (at, rows) = parse_all(23, input, parser)?;
+
+    let mut counts = [0; ROOMS];
+    rows.iter().flatten().for_each(|&a| counts[a] += 1);
+    if counts.iter().any(|&n| n != rows.len()) {
+        return Err(ParseError::new(
+            23,
+            input,
+            at,
+            "as many amphipods of each type as there are rows",
+        ));
+    }
+
+    Ok(Burrow { rows })
+}
+
+#[aoc(day23, part1)]
+pub fn solve_part1(burrow: &Burrow) -> Option<u32> {
+    burrow.min_energy()
+}
+
+#[aoc(day23, part2)]
+pub fn solve_part2(burrow: &Burrow) -> Option<u32> {
+    burrow.insert_rows(1, &FOLDED)?.min_energy()
+}
+
+pub struct Day23;
+
+impl Solution for Day23 {
+    const DAY: u8 = 23;
+
+    type Input = Burrow;
+    type Output1 = Maybe<u32>;
+    type Output2 = Maybe<u32>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        Maybe(solve_part1(input))
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        Maybe(solve_part2(input))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static INPUT: &str = "#############
+#...........#
+###B#C#B#D###
+  #A#D#C#A#
+  #########";
+
+    #[test]
+    fn test_parse() {
+        let burrow = input_generator(INPUT).unwrap();
+        assert_eq!(burrow.rows, [[1, 2, 1, 3], [0, 3, 2, 0]]);
+
+        let unfolded = burrow.insert_rows(1, &FOLDED).unwrap();
+        assert_eq!(unfolded.depth(), 4);
+        assert_eq!(unfolded.rows[1], [3, 2, 1, 0]);
+
+        let err = input_generator(&INPUT.replace("#A#D", "#B#D")).unwrap_err();
+        assert_eq!((err.line, err.column), (3, 1));
+    }
+
+    #[test]
+    fn test_depth() {
+        let sorted = input_generator("#############\n#...........#\n###A#B#C#D###\n  #########");
+        assert_eq!(sorted.unwrap().min_energy(), Some(0));
+
+        let swapped = input_generator("#############\n#...........#\n###B#A#C#D###\n  #########");
+        assert_eq!(swapped.unwrap().min_energy(), Some(46));
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(solve_part1(&input_generator(INPUT).unwrap()), Some(12521))
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(solve_part2(&input_generator(INPUT).unwrap()), Some(44169))
+    }
+}
@@ -0,0 +1,404 @@
+use super::*;
+use crate::solution::Maybe;
+use std::fmt::{self, Display};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand {
+    Register(usize),
+    Value(i64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Inp(usize),
+    Add(usize, Operand),
+    Mul(usize, Operand),
+    Div(usize, Operand),
+    Mod(usize, Operand),
+    Eql(usize, Operand),
+}
+
+/// Why a program could not run to its end, `at` is the index of the failing instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AluError {
+    MissingInput { at: usize },
+    DivisionByZero { at: usize },
+    InvalidModulo { at: usize },
+    Overflow { at: usize },
+}
+
+impl Display for AluError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AluError::MissingInput { at } => write!(f, "instruction {}: no input left", at + 1),
+            AluError::DivisionByZero { at } => {
+                write!(f, "instruction {}: division by zero", at + 1)
+            }
+            AluError::InvalidModulo { at } => {
+                write!(
+                    f,
+                    "instruction {}: modulo of a negative number or by a number below 1",
+                    at + 1
+                )
+            }
+            AluError::Overflow { at } => write!(f, "instruction {}: result out of range", at + 1),
+        }
+    }
+}
+
+impl std::error::Error for AluError {}
+
+#[aoc_generator(day24)]
+pub fn input_generator(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    use crate::parse::{lines, parse_all};
+    use nom::{
+        branch::alt,
+        bytes::complete::tag,
+        character::complete::{char, i64, one_of},
+        combinator::value,
+        error::context,
+        sequence::{preceded, separated_pair},
+        Parser,
+    };
+
+    let register = || {
+        context(
+            "a register",
+            one_of("wxyz").map(|c| c as usize - 'w' as usize),
+        )
+    };
+    let operand = alt((
+        register().map(Operand::Register),
+        context("a register or a number", i64).map(Operand::Value),
+    ));
+
+    let inp = preceded(tag("inp "), register()).map(Instruction::Inp);
+    let op = |name, op: fn(usize, Operand) -> Instruction| value(op, tag(name));
+    let op = alt((
+        op("add ", Instruction::Add),
+        op("mul ", Instruction::Mul),
+        op("div ", Instruction::Div),
+        op("mod ", Instruction::Mod),
+        op("eql ", Instruction::Eql),
+    ));
+    let binary = op
+        .and(separated_pair(register(), char(' '), operand))
+        .map(|(op, (a, b))| op(a, b));
+    let instruction = context(
+        "`inp`, `add`, `mul`, `div`, `mod` or `eql`",
+        alt((inp, binary)),
+    );
+
+    parse_all(24, input, lines(instruction))
+}
+
+/// Runs `program` on `input` and returns the registers `w`, `x`, `y` and `z` at its end.
+pub fn run(
+    program: &[Instruction],
+    input: impl IntoIterator<Item = i64>,
+) -> Result<[i64; 4], AluError> {
+    let mut input = input.into_iter();
+    let mut registers = [0; 4];
+
+    for (at, &instruction) in program.iter().enumerate() {
+        let (a, b) = match instruction {
+            Instruction::Inp(a) => {
+                registers[a] = input.next().ok_or(AluError::MissingInput { at })?;
+                continue;
+            }
+            Instruction::Add(a, b)
+            | Instruction::Mul(a, b)
+            | Instruction::Div(a, b)
+            | Instruction::Mod(a, b)
+            | Instruction::Eql(a, b) => (a, b),
+        };
+        let b = match b {
+            Operand::Register(r) => registers[r],
+            Operand::Value(v) => v,
+        };
+        let a = &mut registers[a];
+
+        let result = match instruction {
+            Instruction::Add(..) => a.checked_add(b),
+            Instruction::Mul(..) => a.checked_mul(b),
+            Instruction::Div(..) if b == 0 => return Err(AluError::DivisionByZero { at }),
+            Instruction::Div(..) => a.checked_div(b),
+            Instruction::Mod(..) if *a < 0 || b <= 0 => return Err(AluError::InvalidModulo { at }),
+            Instruction::Mod(..) => Some(*a % b),
+            Instruction::Eql(..) => Some((*a == b) as i64),
+            Instruction::Inp(_) => unreachable!(),
+        };
+        *a = result.ok_or(AluError::Overflow { at })?;
+    }
+
+    Ok(registers)
+}
+
+/// Whether the MONAD `program` accepts the model number `model`, which must not contain zeros.
+pub fn validate(program: &[Instruction], model: u64) -> Result<bool, AluError> {
+    let digits: Vec<_> = model
+        .to_string()
+        .bytes()
+        .map(|b| (b - b'0') as i64)
+        .collect();
+    if digits.contains(&0) {
+        return Ok(false);
+    }
+
+    Ok(run(program, digits)?[3] == 0)
+}
+
+const DIGITS: usize = 14;
+
+const BLOCK_LEN: usize = 18;
+
+// every digit is checked by a copy of this block, only `div`, `check` and `offset` differ
+fn block(div: i64, check: i64, offset: i64) -> [Instruction; BLOCK_LEN] {
+    use Instruction::*;
+    use Operand::*;
+    let [w, x, y, z] = [0, 1, 2, 3];
+
+    [
+        Inp(w),
+        Mul(x, Value(0)),
+        Add(x, Register(z)),
+        Mod(x, Value(26)),
+        Div(z, Value(div)),
+        Add(x, Value(check)),
+        Eql(x, Register(w)),
+        Eql(x, Value(0)),
+        Mul(y, Value(0)),
+        Add(y, Value(25)),
+        Mul(y, Register(x)),
+        Add(y, Value(1)),
+        Mul(z, Register(y)),
+        Mul(y, Value(0)),
+        Add(y, Register(w)),
+        Add(y, Value(offset)),
+        Mul(y, Register(x)),
+        Add(z, Register(y)),
+    ]
+}
+
+// `z` is used as a stack of base 26 numbers, a block either pushes its digit plus `offset`, or
+// pops the digit of an earlier block and pushes nothing when its own digit equals that one plus
+// `check`
+struct Block {
+    pop: bool,
+    check: i64,
+    offset: i64,
+}
+
+fn blocks(program: &[Instruction]) -> Option<Vec<Block>> {
+    if program.len() != DIGITS * BLOCK_LEN {
+        return None;
+    }
+
+    program
+        .chunks(BLOCK_LEN)
+        .map(|chunk| {
+            let value = |i: usize| match chunk[i] {
+                Instruction::Div(_, Operand::Value(v)) | Instruction::Add(_, Operand::Value(v)) => {
+                    Some(v)
+                }
+                _ => None,
+            };
+            let (div, check, offset) = (value(4)?, value(5)?, value(15)?);
+
+            let pop = match div {
+                // a pushing block must never find its digit on top of the stack, and the pushed
+                // digit plus `offset` has to fit in one base 26 digit
+                1 if check > 9 && (0..=16).contains(&offset) => false,
+                // the popped value is between 1 and 25, the digit of this block between 1 and 9
+                26 if (-24..=8).contains(&check) => true,
+                _ => return None,
+            };
+
+            (*chunk == block(div, check, offset)).then_some(Block { pop, check, offset })
+        })
+        .collect()
+}
+
+/// The largest or smallest model number accepted by `program`, derived from the constraints
+/// between the pairs of blocks that push and pop the same digit.
+///
+/// Returns `None` if the program does not follow the structure of the MONAD blocks, or if no
+/// model number is accepted.
+pub fn model_number(program: &[Instruction], largest: bool) -> Option<u64> {
+    let mut digits = [0; DIGITS];
+    let mut stack = Vec::new();
+
+    for (i, block) in blocks(program)?.into_iter().enumerate() {
+        if !block.pop {
+            stack.push((i, block.offset));
+            continue;
+        }
+
+        // the digit of this block has to be the pushed digit plus `diff`
+        let (j, offset) = stack.pop()?;
+        let diff = offset.checked_add(block.check)?;
+        let (pushed, popped) = if largest {
+            (9.min(9 - diff), 9.min(9 + diff))
+        } else {
+            (1.max(1 - diff), 1.max(1 + diff))
+        };
+        if !(1..=9).contains(&pushed) || !(1..=9).contains(&popped) {
+            return None;
+        }
+        digits[j] = pushed;
+        digits[i] = popped;
+    }
+
+    if !stack.is_empty() {
+        return None;
+    }
+
+    let model = digits.iter().fold(0, |n, &d| n * 10 + d as u64);
+    (validate(program, model) == Ok(true)).then_some(model)
+}
+
+#[aoc(day24, part1)]
+pub fn solve_part1(program: &[Instruction]) -> Option<u64> {
+    model_number(program, true)
+}
+
+#[aoc(day24, part2)]
+pub fn solve_part2(program: &[Instruction]) -> Option<u64> {
+    model_number(program, false)
+}
+
+pub struct Day24;
+
+impl Solution for Day24 {
+    const DAY: u8 = 24;
+
+    type Input = Vec<Instruction>;
+    type Output1 = Maybe<u64>;
+    type Output2 = Maybe<u64>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        Maybe(solve_part1(input))
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        Maybe(solve_part2(input))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // (div, check, offset) of the blocks of a made up MONAD program
+    static BLOCKS: [(i64, i64, i64); DIGITS] = [
+        (1, 11, 6),
+        (1, 13, 14),
+        (26, -8, 2),
+        (26, -12, 5),
+        (1, 10, 1),
+        (1, 12, 3),
+        (1, 14, 10),
+        (26, -10, 8),
+        (26, -1, 4),
+        (26, -5, 7),
+        (1, 15, 7),
+        (26, -3, 1),
+        (1, 11, 0),
+        (26, -8, 9),
+    ];
+
+    fn monad() -> Vec<Instruction> {
+        BLOCKS
+            .iter()
+            .flat_map(|&(div, check, offset)| block(div, check, offset))
+            .collect()
+    }
+
+    #[test]
+    fn test_run() {
+        let negate = input_generator("inp x\nmul x -1").unwrap();
+        assert_eq!(run(&negate, [7]), Ok([0, -7, 0, 0]));
+
+        let triple = input_generator("inp z\ninp x\nmul z 3\neql z x").unwrap();
+        assert_eq!(run(&triple, [2, 6]).unwrap()[3], 1);
+        assert_eq!(run(&triple, [2, 5]).unwrap()[3], 0);
+        assert_eq!(run(&triple, [2]), Err(AluError::MissingInput { at: 1 }));
+
+        let binary = input_generator(
+            "inp w\nadd z w\nmod z 2\ndiv w 2\nadd y w\nmod y 2\ndiv w 2\nadd x w\nmod x 2\ndiv w 2\nmod w 2",
+        )
+        .unwrap();
+        assert_eq!(run(&binary, [13]), Ok([1, 1, 0, 1]));
+
+        let broken = input_generator("inp w\ndiv z w").unwrap();
+        assert_eq!(run(&broken, [0]), Err(AluError::DivisionByZero { at: 1 }));
+
+        let huge = input_generator("inp w\nmul w 9223372036854775807").unwrap();
+        assert_eq!(run(&huge, [1]), Ok([i64::MAX, 0, 0, 0]));
+        assert_eq!(run(&huge, [2]), Err(AluError::Overflow { at: 1 }));
+        let min = input_generator("inp w\nadd w -9223372036854775807\nadd w -1\ndiv w -1").unwrap();
+        assert_eq!(run(&min, [0]), Err(AluError::Overflow { at: 3 }));
+    }
+
+    #[test]
+    fn test_parse() {
+        let text = "inp w\nmul x 0\nadd x z\nmod x 26\ndiv z 1\nadd x 11\neql x w\neql x 0\nmul y 0\nadd y 25\nmul y x\nadd y 1\nmul z y\nmul y 0\nadd y w\nadd y 6\nmul y x\nadd z y";
+        assert_eq!(input_generator(text).unwrap(), block(1, 11, 6));
+
+        let err = input_generator("inp w\nsub x 1").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+
+        let err = input_generator("inp w\nadd v 1").unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.expected.as_str()),
+            (2, 5, "a register")
+        );
+    }
+
+    #[test]
+    fn test_part1() {
+        let program = monad();
+        assert_eq!(solve_part1(&program), Some(93939799955991));
+        assert_eq!(validate(&program, 93939799955991), Ok(true));
+        assert_eq!(validate(&program, 93939799955992), Ok(false));
+    }
+
+    #[test]
+    fn test_part2() {
+        let program = monad();
+        assert_eq!(solve_part2(&program), Some(71715111311591));
+        assert_eq!(validate(&program, 71715111311591), Ok(true));
+        assert_eq!(validate(&program, 71715111311581), Ok(false));
+    }
+
+    #[test]
+    fn test_unsupported_program() {
+        let mut program = monad();
+        program.pop();
+        assert_eq!(solve_part1(&program), None);
+
+        // a pushed digit plus 20 takes more than one base 26 digit
+        let mut blocks = BLOCKS;
+        blocks[1] = (1, 13, 20);
+        blocks[2] = (26, -18, 2);
+        let program: Vec<_> = blocks
+            .iter()
+            .flat_map(|&(d, c, o)| block(d, c, o))
+            .collect();
+        assert_eq!(solve_part1(&program), None);
+        assert_eq!(solve_part2(&program), None);
+
+        let mut blocks = BLOCKS;
+        blocks[2] = (26, i64::MIN, 2);
+        let program: Vec<_> = blocks
+            .iter()
+            .flat_map(|&(d, c, o)| block(d, c, o))
+            .collect();
+        assert_eq!(solve_part1(&program), None);
+        assert_eq!(solve_part2(&program), None);
+    }
+}
@@ -0,0 +1,295 @@
+use super::*;
+use crate::{
+    grid::{Cell, Grid},
+    solution::Maybe,
+};
+use std::fmt::{self, Display};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location {
+    Empty,
+    East,
+    South,
+}
+
+impl Cell for Location {
+    const EXPECTED: &'static str = "`>`, `v` or `.`";
+
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            '.' => Some(Location::Empty),
+            '>' => Some(Location::East),
+            'v' => Some(Location::South),
+            _ => None,
+        }
+    }
+
+    fn to_char(&self) -> char {
+        match self {
+            Location::Empty => '.',
+            Location::East => '>',
+            Location::South => 'v',
+        }
+    }
+}
+
+/// A row of the sea floor with one bit per location.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BitRow {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitRow {
+    fn new(len: usize) -> Self {
+        Self {
+            words: vec![0; len.div_ceil(64)],
+            len,
+        }
+    }
+
+    fn get(&self, i: usize) -> bool {
+        self.words[i / 64] >> (i % 64) & 1 == 1
+    }
+
+    fn set(&mut self, i: usize) {
+        self.words[i / 64] |= 1 << (i % 64);
+    }
+
+    fn any(&self) -> bool {
+        self.words.iter().any(|&w| w != 0)
+    }
+
+    fn zip(&self, other: &BitRow, f: impl Fn(u64, u64) -> u64) -> BitRow {
+        let words = self
+            .words
+            .iter()
+            .zip(&other.words)
+            .map(|(&a, &b)| f(a, b))
+            .collect();
+        BitRow {
+            words,
+            len: self.len,
+        }
+    }
+
+    // bit `i` of the result is bit `i + 1`, wrapping around at the end of the row
+    fn next_bits(&self) -> BitRow {
+        let mut out = self.clone();
+        for (i, word) in out.words.iter_mut().enumerate() {
+            let carry = self.words.get(i + 1).map_or(0, |w| w & 1);
+            *word = *word >> 1 | carry << 63;
+        }
+        if self.get(0) {
+            out.set(self.len - 1);
+        }
+        out
+    }
+
+    // bit `i` of the result is bit `i - 1`, wrapping around at the start of the row
+    fn prev_bits(&self) -> BitRow {
+        let mut out = self.clone();
+        for (i, word) in out.words.iter_mut().enumerate() {
+            let carry = if i > 0 { self.words[i - 1] >> 63 } else { 0 };
+            *word = *word << 1 | carry;
+        }
+        // the last bit was shifted past the end of the row
+        if !self.len.is_multiple_of(64) {
+            *out.words.last_mut().unwrap() &= (1 << (self.len % 64)) - 1;
+        }
+        if self.get(self.len - 1) {
+            out.set(0);
+        }
+        out
+    }
+}
+
+/// Both herds of sea cucumbers, stored as bit rows so that a whole row moves at once.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeaFloor {
+    east: Vec<BitRow>,
+    south: Vec<BitRow>,
+}
+
+impl SeaFloor {
+    pub fn new(grid: &Grid<Location>) -> Self {
+        let mut east = vec![BitRow::new(grid.cols()); grid.rows()];
+        let mut south = east.clone();
+        for ((r, c), location) in grid.indexed_iter() {
+            match location {
+                Location::East => east[r].set(c),
+                Location::South => south[r].set(c),
+                Location::Empty => {}
+            }
+        }
+        Self { east, south }
+    }
+
+    /// Moves the east facing herd and then the south facing one, returns whether anything moved.
+    pub fn step(&mut self) -> bool {
+        let rows = self.east.len();
+        let mut moved = false;
+
+        for r in 0..rows {
+            let occupied = self.east[r].zip(&self.south[r], |e, s| e | s);
+            let movers = self.east[r].zip(&occupied.next_bits(), |e, o| e & !o);
+            if movers.any() {
+                moved = true;
+                self.east[r] = self.east[r].zip(&movers.prev_bits(), |e, m| e | m);
+                self.east[r] = self.east[r].zip(&movers, |e, m| e & !m);
+            }
+        }
+
+        // the whole herd looks at the sea floor before any of it moves
+        let movers: Vec<_> = (0..rows)
+            .map(|r| {
+                let below = (r + 1) % rows;
+                let occupied = self.east[below].zip(&self.south[below], |e, s| e | s);
+                self.south[r].zip(&occupied, |s, o| s & !o)
+            })
+            .collect();
+        for (r, movers) in movers.iter().enumerate().filter(|(_, m)| m.any()) {
+            let below = (r + 1) % rows;
+            moved = true;
+            self.south[r] = self.south[r].zip(movers, |s, m| s & !m);
+            self.south[below] = self.south[below].zip(movers, |s, m| s | m);
+        }
+
+        moved
+    }
+
+    pub fn to_grid(&self) -> Grid<Location> {
+        let cols = self.east.first().map_or(0, |row| row.len);
+        let cells = self
+            .east
+            .iter()
+            .zip(&self.south)
+            .flat_map(|(east, south)| {
+                (0..cols).map(move |c| match (east.get(c), south.get(c)) {
+                    (true, _) => Location::East,
+                    (_, true) => Location::South,
+                    _ => Location::Empty,
+                })
+            })
+            .collect();
+        Grid::new(self.east.len(), cols, cells)
+    }
+}
+
+impl Display for SeaFloor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.to_grid().fmt(f)
+    }
+}
+
+#[aoc_generator(day25)]
+pub fn input_generator(input: &str) -> Result<Grid<Location>, ParseError> {
+    use crate::{grid::grid, parse::parse_all};
+
+    parse_all(25, input, grid)
+}
+
+#[aoc(day25, part1)]
+pub fn solve_part1(grid: &Grid<Location>) -> Option<usize> {
+    let mut floor = SeaFloor::new(grid);
+    // Brent's cycle detection, the state is saved after each power of two steps
+    let mut saved = floor.clone();
+    let mut steps: usize = 1;
+
+    while floor.step() {
+        // back in an earlier state, so the herds keep moving forever
+        if floor == saved {
+            return None;
+        }
+        if steps.is_power_of_two() {
+            saved = floor.clone();
+        }
+        steps += 1;
+    }
+
+    Some(steps)
+}
+
+pub struct Day25;
+
+impl Solution for Day25 {
+    const DAY: u8 = 25;
+
+    type Input = Grid<Location>;
+    type Output1 = Maybe<usize>;
+    type Output2 = Maybe<usize>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        Maybe(solve_part1(input))
+    }
+
+    // the last day only has a single puzzle
+    fn part2(_: &Self::Input) -> Self::Output2 {
+        Maybe(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static INPUT: &str = "v...>>.vv>
+.vv>>.vv..
+>>.>v>...v
+>>v>>.>.v.
+v>v.vv.v..
+>.>>..v...
+.vv..>.>v.
+v.v..>>v.v
+....v..v.>";
+
+    fn steps(input: &str, n: usize) -> String {
+        let mut floor = SeaFloor::new(&input_generator(input).unwrap());
+        for _ in 0..n {
+            floor.step();
+        }
+        floor.to_string()
+    }
+
+    #[test]
+    fn test_step() {
+        assert_eq!(steps("...>>>>>...", 1), "...>>>>.>..");
+        assert_eq!(steps("...>>>>>...", 2), "...>>>.>.>.");
+        assert_eq!(steps(">.>", 1), ".>>");
+
+        let input = "..........\n.>v....v..\n.......>..\n..........";
+        assert_eq!(
+            steps(input, 1),
+            "..........\n.>........\n..v....v>.\n.........."
+        );
+    }
+
+    #[test]
+    fn test_wide_rows() {
+        // rows spanning several words still wrap around at their end
+        let row = format!("{}>", ".".repeat(129));
+        assert_eq!(steps(&row, 1), format!(">{}", ".".repeat(129)));
+        let row = format!("{}>.", ".".repeat(62));
+        assert_eq!(steps(&row, 1), format!("{}>", ".".repeat(63)));
+        assert_eq!(steps(&row, 2), format!(">{}", ".".repeat(63)));
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(solve_part1(&input_generator(INPUT).unwrap()), Some(58))
+    }
+
+    #[test]
+    fn test_cycle() {
+        // the herd moves around the row forever
+        assert_eq!(solve_part1(&input_generator(">.>").unwrap()), None);
+        assert_eq!(solve_part1(&input_generator(">>>").unwrap()), Some(1));
+        // back at the start after 100 steps, past the state saved after 64 steps
+        let long = format!(">{}", ".".repeat(99));
+        assert_eq!(solve_part1(&input_generator(&long).unwrap()), None);
+    }
+}
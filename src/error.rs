@@ -0,0 +1,144 @@
+use nom::{
+    error::{ErrorKind, VerboseError, VerboseErrorKind},
+    Offset,
+};
+use std::fmt;
+
+/// Error produced when a puzzle input can not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u8,
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+    pub found: String,
+}
+
+impl ParseError {
+    /// Creates an error located at the start of `at`, which has to be a slice of `input`.
+    pub fn new(day: u8, input: &str, at: &str, expected: impl Into<String>) -> Self {
+        let offset = input.offset(at);
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+        Self {
+            day,
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            expected: expected.into(),
+            found: describe(at),
+        }
+    }
+
+    pub(crate) fn from_nom(day: u8, input: &str, err: nom::Err<VerboseError<&str>>) -> Self {
+        // `new` needs a slice of `input`, a literal "" could be anywhere
+        let end = &input[input.len()..];
+        let errors = match err {
+            nom::Err::Error(e) | nom::Err::Failure(e) => e.errors,
+            nom::Err::Incomplete(_) => return Self::new(day, input, end, "more input"),
+        };
+
+        let at = errors.first().map_or(end, |(at, _)| at);
+        let expected = errors
+            .iter()
+            .find_map(|(_, kind)| match kind {
+                VerboseErrorKind::Context(ctx) => Some(ctx.to_string()),
+                _ => None,
+            })
+            .or_else(|| {
+                errors.first().map(|(_, kind)| match kind {
+                    VerboseErrorKind::Char(c) => describe_char(*c),
+                    VerboseErrorKind::Nom(kind) => describe_kind(*kind),
+                    VerboseErrorKind::Context(ctx) => ctx.to_string(),
+                })
+            })
+            .unwrap_or_else(|| "valid input".to_owned());
+
+        Self::new(day, input, at, expected)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "day {}, line {}, column {}: expected {}, found {}",
+            self.day, self.line, self.column, self.expected, self.found
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+fn describe_char(c: char) -> String {
+    match c {
+        '\n' | '\r' => "end of line".to_owned(),
+        ' ' => "space".to_owned(),
+        c => format!("`{}`", c),
+    }
+}
+
+fn describe_kind(kind: ErrorKind) -> String {
+    let description = match kind {
+        ErrorKind::Digit => "a number",
+        ErrorKind::HexDigit => "a hexadecimal digit",
+        ErrorKind::Alpha => "letters",
+        ErrorKind::CrLf => "end of line",
+        ErrorKind::Eof => "end of input",
+        ErrorKind::MapRes | ErrorKind::Verify => "a valid value",
+        ErrorKind::Satisfy | ErrorKind::IsA | ErrorKind::OneOf => "a valid character",
+        ErrorKind::Many1 | ErrorKind::SeparatedList => "at least one item",
+        _ => kind.description(),
+    };
+    description.to_owned()
+}
+
+// name the token at the start of `at`
+fn describe(at: &str) -> String {
+    match at.chars().next() {
+        None => "end of input".to_owned(),
+        Some(c) if c.is_whitespace() => describe_char(c),
+        Some(_) => {
+            let token: String = at
+                .chars()
+                .take_while(|c| !c.is_whitespace())
+                .take(20)
+                .collect();
+            format!("`{}`", token)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position() {
+        let input = "199\n200\n2x8";
+        let err = ParseError::new(1, input, &input[9..], "a number");
+        assert_eq!((err.line, err.column), (3, 2));
+        assert_eq!(err.found, "`x8`");
+        assert_eq!(
+            err.to_string(),
+            "day 1, line 3, column 2: expected a number, found `x8`"
+        );
+
+        let err = ParseError::new(1, input, &input[input.len()..], "a number");
+        assert_eq!((err.line, err.column), (3, 4));
+        assert_eq!(err.found, "end of input");
+    }
+
+    #[test]
+    fn test_from_nom() {
+        let input = "199\n200";
+        let incomplete = nom::Err::Incomplete(nom::Needed::Unknown);
+        let err = ParseError::from_nom(1, input, incomplete);
+        assert_eq!((err.line, err.column), (2, 4));
+        assert_eq!(err.expected, "more input");
+
+        let empty = nom::Err::Error(VerboseError { errors: vec![] });
+        let err = ParseError::from_nom(1, input, empty);
+        assert_eq!((err.line, err.column), (2, 4));
+    }
+}
@@ -0,0 +1,322 @@
+use crate::parse::{lines, IResult};
+use nom::error::{ErrorKind, VerboseError, VerboseErrorKind};
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+const NEIGHBORS4: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+const NEIGHBORS8: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// A single character of a grid input.
+pub trait Cell: Sized {
+    /// What a cell looks like, used in parse errors.
+    const EXPECTED: &'static str;
+
+    fn from_char(c: char) -> Option<Self>;
+    fn to_char(&self) -> char;
+}
+
+impl Cell for u8 {
+    const EXPECTED: &'static str = "a digit";
+
+    fn from_char(c: char) -> Option<Self> {
+        c.to_digit(10).map(|d| d as u8)
+    }
+
+    fn to_char(&self) -> char {
+        char::from_digit(*self as u32, 10).unwrap_or('?')
+    }
+}
+
+impl Cell for u32 {
+    const EXPECTED: &'static str = "a digit";
+
+    fn from_char(c: char) -> Option<Self> {
+        c.to_digit(10)
+    }
+
+    fn to_char(&self) -> char {
+        char::from_digit(*self, 10).unwrap_or('?')
+    }
+}
+
+impl Cell for bool {
+    const EXPECTED: &'static str = "`.` or `#`";
+
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            '.' => Some(false),
+            '#' => Some(true),
+            _ => None,
+        }
+    }
+
+    fn to_char(&self) -> char {
+        if *self {
+            '#'
+        } else {
+            '.'
+        }
+    }
+}
+
+/// Rectangular grid stored row by row, positions are `(row, col)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    rows: usize,
+    cols: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(rows: usize, cols: usize, cells: Vec<T>) -> Self {
+        assert_eq!(
+            rows * cols,
+            cells.len(),
+            "grid size does not match its cells"
+        );
+        Self { rows, cols, cells }
+    }
+
+    pub fn from_elem(rows: usize, cols: usize, elem: T) -> Self
+    where
+        T: Clone,
+    {
+        Self::new(rows, cols, vec![elem; rows * cols])
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn get(&self, (r, c): (usize, usize)) -> Option<&T> {
+        (r < self.rows && c < self.cols).then(|| &self.cells[r * self.cols + c])
+    }
+
+    pub fn get_mut(&mut self, (r, c): (usize, usize)) -> Option<&mut T> {
+        (r < self.rows && c < self.cols).then(|| &mut self.cells[r * self.cols + c])
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut()
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let cols = self.cols;
+        (0..self.rows).flat_map(move |r| (0..cols).map(move |c| (r, c)))
+    }
+
+    pub fn indexed_iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid::new(self.rows, self.cols, self.cells.iter().map(f).collect())
+    }
+
+    fn offset(&self, (r, c): (usize, usize), (dr, dc): (isize, isize)) -> Option<(usize, usize)> {
+        let r = r.checked_add_signed(dr)?;
+        let c = c.checked_add_signed(dc)?;
+        (r < self.rows && c < self.cols).then_some((r, c))
+    }
+
+    /// Positions directly above, left, right and below of `pos` that are inside the grid.
+    pub fn neighbors4(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        NEIGHBORS4
+            .into_iter()
+            .filter_map(move |d| self.offset(pos, d))
+    }
+
+    /// Positions around `pos`, including diagonals, that are inside the grid.
+    pub fn neighbors8(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        NEIGHBORS8
+            .into_iter()
+            .filter_map(move |d| self.offset(pos, d))
+    }
+
+    /// The 3x3 block centered on `pos` in reading order, `None` outside of the grid.
+    pub fn neighborhood(&self, pos: (usize, usize)) -> impl Iterator<Item = Option<&T>> + '_ {
+        (-1..=1)
+            .flat_map(|dr| (-1..=1).map(move |dc| (dr, dc)))
+            .map(move |d| self.offset(pos, d).map(|p| &self[p]))
+    }
+
+    /// Surrounds the grid with `n` rows and columns of `fill` on every side.
+    pub fn pad(&self, n: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        let cols = self.cols + 2 * n;
+        let mut cells = vec![fill.clone(); n * cols];
+        // by index rather than `chunks`, a grid without columns still has its rows
+        for r in 0..self.rows {
+            cells.extend(std::iter::repeat_n(fill.clone(), n));
+            cells.extend_from_slice(&self.cells[r * self.cols..(r + 1) * self.cols]);
+            cells.extend(std::iter::repeat_n(fill.clone(), n));
+        }
+        cells.extend(std::iter::repeat_n(fill, n * cols));
+
+        Self::new(self.rows + 2 * n, cols, cells)
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: (usize, usize)) -> &T {
+        self.get(pos).expect("position outside of the grid")
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, pos: (usize, usize)) -> &mut T {
+        self.get_mut(pos).expect("position outside of the grid")
+    }
+}
+
+impl<T: Cell> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (r, row) in self.cells.chunks(self.cols.max(1)).enumerate() {
+            if r > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell.to_char())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn error(at: &str, kind: VerboseErrorKind) -> VerboseError<&str> {
+    VerboseError {
+        errors: vec![(at, kind)],
+    }
+}
+
+/// Parses a single cell of a character map.
+pub fn cell<T: Cell>(input: &str) -> IResult<'_, T> {
+    let mut chars = input.chars();
+    match chars.next().and_then(T::from_char) {
+        Some(cell) => Ok((chars.as_str(), cell)),
+        None => Err(nom::Err::Error(error(
+            input,
+            VerboseErrorKind::Context(T::EXPECTED),
+        ))),
+    }
+}
+
+/// Parses a character map with one row per line, every row has to be as wide as the first.
+pub fn grid<'a, T: Cell>(input: &'a str) -> IResult<'a, Grid<T>> {
+    let mut cols = None;
+
+    let row = |line: &'a str| -> IResult<'a, Vec<T>> {
+        let mut rest = line;
+        let mut row = Vec::new();
+        while let Ok((r, cell)) = cell::<T>(rest) {
+            if cols == Some(row.len()) {
+                let kind = VerboseErrorKind::Nom(ErrorKind::CrLf);
+                return Err(nom::Err::Failure(error(rest, kind)));
+            }
+            row.push(cell);
+            rest = r;
+        }
+
+        match cols {
+            _ if row.is_empty() => Err(nom::Err::Error(error(
+                rest,
+                VerboseErrorKind::Context(T::EXPECTED),
+            ))),
+            Some(cols) if row.len() < cols => Err(nom::Err::Failure(error(
+                rest,
+                VerboseErrorKind::Context(T::EXPECTED),
+            ))),
+            _ => {
+                cols = Some(row.len());
+                Ok((rest, row))
+            }
+        }
+    };
+
+    let (rest, rows) = lines(row)(input)?;
+    let (num_rows, cols) = (rows.len(), rows[0].len());
+    let cells = rows.into_iter().flatten().collect();
+    Ok((rest, Grid::new(num_rows, cols, cells)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parse_all;
+
+    #[test]
+    fn test_neighbors() {
+        let grid = Grid::new(2, 3, (0..6).collect::<Vec<u8>>());
+        assert_eq!(grid.to_string(), "012\n345");
+        assert_eq!(grid.get((2, 0)), None);
+
+        let n4: Vec<_> = grid.neighbors4((0, 0)).collect();
+        assert_eq!(n4, [(0, 1), (1, 0)]);
+        let n8: Vec<_> = grid.neighbors8((1, 1)).map(|p| grid[p]).collect();
+        assert_eq!(n8, [0, 1, 2, 3, 5]);
+        let block: Vec<_> = grid.neighborhood((0, 2)).map(|v| v.copied()).collect();
+        assert_eq!(
+            block,
+            [
+                None,
+                None,
+                None,
+                Some(1),
+                Some(2),
+                None,
+                Some(4),
+                Some(5),
+                None
+            ]
+        );
+
+        assert_eq!(grid.pad(1, 0).to_string(), "00000\n00120\n03450\n00000");
+
+        let empty = Grid::<u8>::new(0, 0, vec![]).pad(1, 7);
+        assert_eq!(empty.to_string(), "77\n77");
+        let narrow = Grid::<u8>::new(3, 0, vec![]).pad(1, 7);
+        assert_eq!((narrow.rows(), narrow.cols()), (5, 2));
+    }
+
+    #[test]
+    fn test_parse() {
+        let map: Grid<bool> = parse_all(0, "#..\n.#.\n", grid).unwrap();
+        assert_eq!((map.rows(), map.cols()), (2, 3));
+        assert_eq!(map.to_string(), "#..\n.#.");
+
+        let err = parse_all(0, "123\n45\n678", grid::<u8>).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+        assert_eq!(err.expected, "a digit");
+
+        let err = parse_all(0, "123\n4567", grid::<u8>).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 4));
+        assert_eq!(err.expected, "end of line");
+
+        let err = parse_all(0, "123\n4x6", grid::<u8>).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+        assert_eq!(err.expected, "a digit");
+    }
+}
@@ -1,5 +1,16 @@
 use aoc_runner_derive::aoc_lib;
 use aoc_runner_derive::{aoc, aoc_generator};
+use error::ParseError;
+use solution::Solution;
+
+pub mod bignum;
+pub mod bingo;
+pub mod error;
+pub mod grid;
+pub mod parse;
+pub mod solution;
+#[cfg(test)]
+mod test_util;
 
 pub mod day01;
 pub mod day02;
@@ -12,9 +23,19 @@ pub mod day08;
 pub mod day09;
 pub mod day10;
 pub mod day11;
+pub mod day12;
 pub mod day13;
 pub mod day14;
+pub mod day15;
 pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
 pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
 
 aoc_lib! { year = 2021 }
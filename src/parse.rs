@@ -0,0 +1,185 @@
+use crate::error::ParseError;
+use nom::{
+    bytes::complete::tag,
+    character::complete::{char, line_ending, space0, u32},
+    combinator::value,
+    error::{context, ErrorKind, ParseError as _, VerboseError},
+    sequence::{separated_pair, tuple},
+    Parser,
+};
+
+pub type IResult<'a, O> = nom::IResult<&'a str, O, VerboseError<&'a str>>;
+
+/// Runs `parser` over the whole `input` of `day`, ignoring trailing whitespace.
+pub fn parse_all<'a, O>(
+    day: u8,
+    input: &'a str,
+    mut parser: impl Parser<&'a str, O, VerboseError<&'a str>>,
+) -> Result<O, ParseError> {
+    match parser.parse(input.trim_end()) {
+        Ok(("", out)) => Ok(out),
+        Ok((rest, _)) => Err(ParseError::new(day, input, rest, "end of input")),
+        Err(e) => Err(ParseError::from_nom(day, input, e)),
+    }
+}
+
+// once a separator matched, whatever follows it has to parse
+fn fatal<E>(e: nom::Err<E>) -> nom::Err<E> {
+    match e {
+        nom::Err::Error(e) => nom::Err::Failure(e),
+        e => e,
+    }
+}
+
+fn is_blank(line: &str) -> bool {
+    let line = line.trim_start_matches([' ', '\t']);
+    line.is_empty() || line_ending::<_, ()>(line).is_ok()
+}
+
+/// Parses one `item` per line until an empty line or the end of the input.
+///
+/// Once the first item parsed, a broken line is reported as such instead of silently ending the
+/// list there.
+pub fn lines<'a, O>(
+    mut item: impl Parser<&'a str, O, VerboseError<&'a str>>,
+) -> impl FnMut(&'a str) -> IResult<'a, Vec<O>> {
+    move |input| {
+        let (mut input, first) = item.parse(input)?;
+        let mut out = vec![first];
+
+        loop {
+            let next = match line_ending::<_, VerboseError<&str>>(input) {
+                Ok((next, _)) => next,
+                Err(_) if input.is_empty() => return Ok((input, out)),
+                Err(_) => {
+                    return Err(nom::Err::Failure(VerboseError::from_error_kind(
+                        input,
+                        ErrorKind::CrLf,
+                    )))
+                }
+            };
+
+            if is_blank(next) {
+                return Ok((input, out));
+            }
+
+            let (rest, o) = item.parse(next).map_err(fatal)?;
+            out.push(o);
+            input = rest;
+        }
+    }
+}
+
+fn separated<'a, O, S>(
+    mut sep: impl Parser<&'a str, S, VerboseError<&'a str>>,
+    mut item: impl Parser<&'a str, O, VerboseError<&'a str>>,
+) -> impl FnMut(&'a str) -> IResult<'a, Vec<O>> {
+    move |input| {
+        let (mut input, first) = item.parse(input)?;
+        let mut out = vec![first];
+
+        while let Ok((next, _)) = sep.parse(input) {
+            let (rest, o) = item.parse(next).map_err(fatal)?;
+            out.push(o);
+            input = rest;
+        }
+
+        Ok((input, out))
+    }
+}
+
+/// Parses comma separated values on a single line, like `3,4,3,1,2`.
+pub fn comma_list<'a, O>(
+    item: impl Parser<&'a str, O, VerboseError<&'a str>>,
+) -> impl FnMut(&'a str) -> IResult<'a, Vec<O>> {
+    separated(char(','), item)
+}
+
+/// Parses an empty line, which may contain spaces.
+pub fn blank_line(input: &str) -> IResult<'_, ()> {
+    value((), tuple((line_ending, space0, line_ending)))(input)
+}
+
+/// Parses two parts of the input separated by an empty line.
+pub fn sections<'a, A, B>(
+    first: impl Parser<&'a str, A, VerboseError<&'a str>>,
+    second: impl Parser<&'a str, B, VerboseError<&'a str>>,
+) -> impl FnMut(&'a str) -> IResult<'a, (A, B)> {
+    separated_pair(first, context("an empty line", blank_line), second)
+}
+
+/// Parses blocks of lines separated by empty lines.
+pub fn blocks<'a, O>(
+    item: impl Parser<&'a str, O, VerboseError<&'a str>>,
+) -> impl FnMut(&'a str) -> IResult<'a, Vec<O>> {
+    separated(blank_line, item)
+}
+
+/// Parses a `x,y` coordinate.
+pub fn coord(input: &str) -> IResult<'_, (u32, u32)> {
+    separated_pair(
+        context("a coordinate", u32),
+        char(','),
+        context("a coordinate", u32),
+    )(input)
+}
+
+/// Parses a `x1,y1 -> x2,y2` pair of coordinates.
+pub fn coord_pair(input: &str) -> IResult<'_, ((u32, u32), (u32, u32))> {
+    separated_pair(coord, context("` -> `", tag(" -> ")), coord)(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines() {
+        assert_eq!(parse_all(1, "1\n2\n3\n", lines(u32)), Ok(vec![1, 2, 3]));
+
+        let err = parse_all(1, "1\n2\nx\n4", lines(u32)).unwrap_err();
+        assert_eq!((err.line, err.column), (3, 1));
+        assert_eq!(err.expected, "a number");
+        assert_eq!(err.found, "`x`");
+
+        let err = parse_all(1, "1\n2y\n3", lines(u32)).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+        assert_eq!(err.expected, "end of line");
+
+        let (rest, nums) = lines(u32)("1\n2\n\n3").unwrap();
+        assert_eq!((rest, nums), ("\n\n3", vec![1, 2]));
+    }
+
+    #[test]
+    fn test_comma_list() {
+        assert_eq!(parse_all(6, "3,4,3\n", comma_list(u32)), Ok(vec![3, 4, 3]));
+        assert_eq!(
+            parse_all(6, "3,4,3\r\n", comma_list(u32)),
+            Ok(vec![3, 4, 3])
+        );
+
+        let err = parse_all(6, "3,4,,3", comma_list(u32)).unwrap_err();
+        assert_eq!((err.line, err.column), (1, 5));
+        assert_eq!(err.expected, "a number");
+    }
+
+    #[test]
+    fn test_sections() {
+        let input = "1,2\r\n\r\n1\r\n2\r\n \r\n3\r\n";
+        let parser = sections(comma_list(u32), blocks(lines(u32)));
+        assert_eq!(
+            parse_all(4, input, parser),
+            Ok((vec![1, 2], vec![vec![1, 2], vec![3]]))
+        );
+
+        let err = parse_all(4, "1,2\n3", sections(coord, lines(u32))).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+        assert_eq!(err.expected, "an empty line");
+    }
+
+    #[test]
+    fn test_coord_pair() {
+        assert_eq!(coord_pair("0,9 -> 5,9"), Ok(("", ((0, 9), (5, 9)))));
+        assert!(coord_pair("0,9 - 5,9").is_err());
+    }
+}
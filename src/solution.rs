@@ -0,0 +1,122 @@
+use crate::*;
+use std::fmt::{self, Display};
+
+/// Common shape of every day: parse the input once, then solve both parts on it.
+pub trait Solution {
+    const DAY: u8;
+
+    type Input;
+    type Output1: Display;
+    type Output2: Display;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+    fn part1(input: &Self::Input) -> Self::Output1;
+    fn part2(input: &Self::Input) -> Self::Output2;
+}
+
+/// Answer that does not exist for every input.
+pub struct Maybe<T>(pub Option<T>);
+
+impl<T: Display> Display for Maybe<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            Some(answer) => answer.fmt(f),
+            None => f.write_str("no answer"),
+        }
+    }
+}
+
+/// A parsed input of some day, with the concrete types erased.
+pub trait Parsed {
+    fn part1(&self) -> Box<dyn Display + '_>;
+    fn part2(&self) -> Box<dyn Display + '_>;
+}
+
+struct Prepared<S: Solution>(S::Input);
+
+impl<S: Solution> Parsed for Prepared<S> {
+    fn part1(&self) -> Box<dyn Display + '_> {
+        Box::new(S::part1(&self.0))
+    }
+
+    fn part2(&self) -> Box<dyn Display + '_> {
+        Box::new(S::part2(&self.0))
+    }
+}
+
+fn prepare<S: Solution + 'static>(input: &str) -> Result<Box<dyn Parsed>, ParseError> {
+    S::parse(input).map(|input| Box::new(Prepared::<S>(input)) as Box<dyn Parsed>)
+}
+
+type ParseFn = fn(&str) -> Result<Box<dyn Parsed>, ParseError>;
+
+/// Entry of the day registry.
+pub struct Day {
+    pub day: u8,
+    parse: ParseFn,
+}
+
+impl Day {
+    pub const fn new<S: Solution + 'static>() -> Self {
+        Self {
+            day: S::DAY,
+            parse: prepare::<S>,
+        }
+    }
+
+    pub fn parse(&self, input: &str) -> Result<Box<dyn Parsed>, ParseError> {
+        (self.parse)(input)
+    }
+}
+
+/// All implemented days, in order.
+pub const DAYS: &[Day] = &[
+    Day::new::<day01::Day01>(),
+    Day::new::<day02::Day02>(),
+    Day::new::<day03::Day03>(),
+    Day::new::<day04::Day04>(),
+    Day::new::<day05::Day05>(),
+    Day::new::<day06::Day06>(),
+    Day::new::<day07::Day07>(),
+    Day::new::<day08::Day08>(),
+    Day::new::<day09::Day09>(),
+    Day::new::<day10::Day10>(),
+    Day::new::<day11::Day11>(),
+    Day::new::<day12::Day12>(),
+    Day::new::<day13::Day13>(),
+    Day::new::<day14::Day14>(),
+    Day::new::<day15::Day15>(),
+    Day::new::<day16::Day16>(),
+    Day::new::<day17::Day17>(),
+    Day::new::<day18::Day18>(),
+    Day::new::<day19::Day19>(),
+    Day::new::<day20::Day20>(),
+    Day::new::<day21::Day21>(),
+    Day::new::<day22::Day22>(),
+    Day::new::<day23::Day23>(),
+    Day::new::<day24::Day24>(),
+    Day::new::<day25::Day25>(),
+];
+
+pub fn find(day: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.day == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry() {
+        assert!(DAYS.windows(2).all(|d| d[0].day < d[1].day));
+
+        let day = find(1).unwrap();
+        let parsed = day
+            .parse("199\n200\n208\n210\n200\n207\n240\n269\n260\n263")
+            .unwrap();
+        assert_eq!(parsed.part1().to_string(), "7");
+        assert_eq!(parsed.part2().to_string(), "5");
+
+        assert!(find(0).is_none());
+    }
+}
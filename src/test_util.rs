@@ -0,0 +1,16 @@
+use std::ops::Range;
+
+/// Deterministic pseudo random numbers for tests, the same seed always gives the same sequence.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    /// A number in `range`, which must not be empty.
+    pub fn range(&mut self, range: Range<i32>) -> i32 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1);
+        range.start + ((self.0 >> 33) % (range.end - range.start) as u64) as i32
+    }
+}